@@ -69,6 +69,9 @@ pub mod _06_module {
 
     #[doc = include_str!("../doc/_06_module/_07_register_interface/index.md")]
     pub mod _07_register_interface {}
+
+    #[doc = include_str!("../doc/_06_module/_08_register_enum/index.md")]
+    pub mod _08_register_enum {}
 }
 
 /// TODO
@@ -0,0 +1,357 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::functions::{typed_arguments, wrap_result, TypedArgument};
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    meta::{self, ParseNestedMeta},
+    parse_macro_input,
+    spanned::Spanned,
+    Error, Expr, Ident, ImplItem, ImplItemFn, ItemImpl, ItemStruct, LitStr,
+};
+
+pub(crate) fn php_class(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut name = None;
+    let mut properties = Vec::new();
+    let parser = meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("property") {
+            properties.push(Property::parse(&meta)?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported php_class property"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    let input = parse_macro_input!(input as ItemStruct);
+
+    match expand_class(name, &properties, &input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The property declared by `property(name = "...", default = ...)`.
+struct Property {
+    name: LitStr,
+    default: Expr,
+    visibility: Ident,
+    is_static: bool,
+}
+
+impl Property {
+    fn parse(meta: &ParseNestedMeta<'_>) -> syn::Result<Self> {
+        let mut name = None;
+        let mut default = None;
+        let mut visibility = Ident::new("Public", Span::call_site());
+        let mut is_static = false;
+
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("static") {
+                is_static = true;
+            } else if let Some(vis) = parse_visibility(&meta) {
+                visibility = vis;
+            } else {
+                return Err(meta.error("unsupported property attribute"));
+            }
+            Ok(())
+        })?;
+
+        Ok(Self {
+            name: name.ok_or_else(|| meta.error("property requires `name`"))?,
+            default: default.ok_or_else(|| meta.error("property requires `default`"))?,
+            visibility,
+            is_static,
+        })
+    }
+}
+
+/// Parses the visibility flags `public`, `protected` and `private`, into the
+/// variant of `phper::classes::Visibility`.
+fn parse_visibility(meta: &ParseNestedMeta<'_>) -> Option<Ident> {
+    ["public", "protected", "private"]
+        .iter()
+        .find(|vis| meta.path.is_ident(vis))
+        .map(|vis| {
+            let variant = vis[..1].to_uppercase() + &vis[1..];
+            Ident::new(&variant, meta.path.span())
+        })
+}
+
+fn expand_class(
+    name: Option<String>,
+    properties: &[Property],
+    input: &ItemStruct,
+) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic structs can't be registered to php",
+        ));
+    }
+
+    let ident = &input.ident;
+    let php_name = name.unwrap_or_else(|| ident.to_string());
+
+    let properties = properties.iter().map(|property| {
+        let Property {
+            name,
+            default,
+            visibility,
+            is_static,
+        } = property;
+        let method = if *is_static {
+            quote! { add_static_property }
+        } else {
+            quote! { add_property }
+        };
+        quote! {
+            class.#method(#name, ::phper::classes::Visibility::#visibility, #default);
+        }
+    });
+
+    Ok(quote! {
+        #input
+
+        impl ::phper::classes::PhpClass for #ident {
+            fn static_class() -> &'static ::phper::classes::StaticStateClass {
+                static CLASS: ::phper::classes::StaticStateClass =
+                    ::phper::classes::StaticStateClass::null();
+                &CLASS
+            }
+
+            fn class_entity() -> ::phper::classes::entity::ClassEntity {
+                let mut class = <Self as ::phper::classes::PhpImpl>::new_class_entity(#php_name);
+                class.bind(<Self as ::phper::classes::PhpClass>::static_class());
+                #(#properties)*
+                <Self as ::phper::classes::PhpImpl>::register_methods(&mut class);
+                class
+            }
+        }
+    })
+}
+
+pub(crate) fn php_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ItemImpl);
+
+    match expand_impl(&mut input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The options of method, declared by `#[php(...)]`.
+struct MethodOptions {
+    name: Option<String>,
+    visibility: Ident,
+    is_constructor: bool,
+}
+
+impl MethodOptions {
+    /// Parses and removes the `#[php(...)]` attributes of method.
+    fn take(method: &mut ImplItemFn) -> syn::Result<Self> {
+        let mut options = Self {
+            name: None,
+            visibility: Ident::new("Public", Span::call_site()),
+            is_constructor: false,
+        };
+
+        let mut result = Ok(());
+        method.attrs.retain(|attr| {
+            if !attr.path().is_ident("php") {
+                return true;
+            }
+            if result.is_ok() {
+                result = attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        options.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    } else if meta.path.is_ident("constructor") {
+                        options.is_constructor = true;
+                    } else if let Some(vis) = parse_visibility(&meta) {
+                        options.visibility = vis;
+                    } else {
+                        return Err(meta.error("unsupported php method attribute"));
+                    }
+                    Ok(())
+                });
+            }
+            false
+        });
+        result.map(|_| options)
+    }
+}
+
+fn expand_impl(input: &mut ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(Error::new(
+            path.span(),
+            "php_impl should be used on inherent impl",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic impls can't be registered to php",
+        ));
+    }
+
+    let mut constructor = None;
+    let mut registers = Vec::new();
+    for item in &mut input.items {
+        if let ImplItem::Fn(method) = item {
+            let options = MethodOptions::take(method)?;
+            if !options.is_constructor {
+                registers.push(expand_method(method, options)?);
+            } else if constructor.is_none() {
+                constructor = Some(expand_constructor(method, options)?);
+            } else {
+                return Err(Error::new(
+                    method.sig.ident.span(),
+                    "class can only have one constructor",
+                ));
+            }
+        }
+    }
+
+    // Without constructor, the state is built by `Default`.
+    let constructor = constructor.unwrap_or_else(|| {
+        quote! {
+            ::phper::classes::entity::ClassEntity::
+                new_with_default_state_constructor::<Self>(class_name)
+        }
+    });
+
+    let self_ty = &input.self_ty;
+
+    Ok(quote! {
+        #input
+
+        impl ::phper::classes::PhpImpl for #self_ty {
+            fn new_class_entity(class_name: &str) -> ::phper::classes::entity::ClassEntity {
+                #constructor
+            }
+
+            fn register_methods(class: &mut ::phper::classes::entity::ClassEntity) {
+                #(#registers)*
+            }
+        }
+    })
+}
+
+/// Expands the constructor, which builds the state from the arguments of
+/// `__construct`, so the state doesn't need `Default`.
+fn expand_constructor(method: &ImplItemFn, options: MethodOptions) -> syn::Result<TokenStream2> {
+    let sig = &method.sig;
+    let ident = &sig.ident;
+    if let Some(receiver) = sig.receiver() {
+        return Err(Error::new(
+            receiver.span(),
+            "constructor should return `Self` instead of receiving `self`",
+        ));
+    }
+    if options.visibility != "Public" || options.name.is_some() {
+        return Err(Error::new(
+            ident.span(),
+            "constructor can't be renamed or non-public",
+        ));
+    }
+    let arguments = typed_arguments(sig)?;
+    let idents = arguments
+        .iter()
+        .map(|argument| &argument.ident)
+        .collect::<Vec<_>>();
+    let tys = arguments
+        .iter()
+        .map(|argument| &argument.ty)
+        .collect::<Vec<_>>();
+    let names = arguments.iter().map(|argument| &argument.php_name);
+    let len = arguments.len();
+    let body = wrap_result(&sig.output, quote! { Self::#ident(#(#idents),*) });
+    Ok(quote! {
+        ::phper::classes::entity::ClassEntity::new_with_typed_constructor(
+            class_name,
+            [#(#names),*] as [&str; #len],
+            |#(#idents: #tys),*| #body,
+        )
+    })
+}
+
+fn expand_method(method: &ImplItemFn, options: MethodOptions) -> syn::Result<TokenStream2> {
+    let sig = &method.sig;
+    let ident = &sig.ident;
+    let visibility = &options.visibility;
+    let arguments = typed_arguments(sig)?;
+    let idents = arguments
+        .iter()
+        .map(|argument| &argument.ident)
+        .collect::<Vec<_>>();
+    let tys = arguments
+        .iter()
+        .map(|argument| &argument.ty)
+        .collect::<Vec<_>>();
+    let argument_names = argument_names(&arguments);
+    let this = Ident::new("__phper_this", Span::mixed_site());
+
+    let php_name = options.name.unwrap_or_else(|| ident.to_string());
+
+    match sig.receiver() {
+        Some(receiver) => {
+            if receiver.reference.is_none() {
+                return Err(Error::new(
+                    receiver.span(),
+                    "methods should receive `&self` or `&mut self`",
+                ));
+            }
+            let state = if receiver.mutability.is_some() {
+                quote! { #this.as_mut_state::<Self>() }
+            } else {
+                quote! { #this.as_state::<Self>() }
+            };
+            let body = wrap_result(&sig.output, quote! { Self::#ident(#state, #(#idents),*) });
+            Ok(quote! {
+                class
+                    .add_typed_method(
+                        #php_name,
+                        ::phper::classes::Visibility::#visibility,
+                        |#this: &mut ::phper::objects::StateObj, #(#idents: #tys),*| #body,
+                    )
+                    #argument_names;
+            })
+        }
+        None => {
+            let body = wrap_result(&sig.output, quote! { Self::#ident(#(#idents),*) });
+            Ok(quote! {
+                class
+                    .add_typed_static_method(
+                        #php_name,
+                        ::phper::classes::Visibility::#visibility,
+                        |#(#idents: #tys),*| #body,
+                    )
+                    #argument_names;
+            })
+        }
+    }
+}
+
+fn argument_names(arguments: &[TypedArgument]) -> Option<TokenStream2> {
+    (!arguments.is_empty()).then(|| {
+        let names = arguments.iter().map(|argument| &argument.php_name);
+        quote! { .argument_names([#(#names),*]) }
+    })
+}
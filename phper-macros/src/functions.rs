@@ -0,0 +1,173 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    meta, parse_macro_input, spanned::Spanned, Attribute, Error, FnArg, Ident, ItemFn, LitStr, Pat,
+    ReturnType, Signature, Type,
+};
+
+pub(crate) fn php_function(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut name = None;
+    let parser = meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            Err(meta.error("unsupported php_function property"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    let input = parse_macro_input!(input as ItemFn);
+
+    match expand_function(name, &input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_function(name: Option<String>, input: &ItemFn) -> syn::Result<TokenStream2> {
+    let vis = &input.vis;
+    let ident = &input.sig.ident;
+    let entity_ident = format_ident!("{}_entity", ident);
+    let php_name = name.unwrap_or_else(|| ident.to_string());
+    let docs = doc_attrs(&input.attrs);
+    let doc_link = format!("The PHP function entity of [`{}`].", ident);
+
+    if let Some(receiver) = input.sig.receiver() {
+        return Err(Error::new(
+            receiver.span(),
+            "`self` isn't supported by php_function",
+        ));
+    }
+
+    let arguments = typed_arguments(&input.sig)?;
+    let handler = typed_handler(&input.sig, quote! { #ident }, &arguments);
+    let argument_names = (!arguments.is_empty()).then(|| {
+        let names = arguments.iter().map(|argument| &argument.php_name);
+        quote! { entity.argument_names([#(#names),*]); }
+    });
+
+    Ok(quote! {
+        #input
+
+        #[doc = #doc_link]
+        #[doc = ""]
+        #(#docs)*
+        #vis fn #entity_ident() -> ::phper::functions::FunctionEntity {
+            #[allow(unused_mut)]
+            let mut entity = ::phper::functions::FunctionEntity::new_typed(#php_name, #handler);
+            #argument_names
+            entity
+        }
+    })
+}
+
+/// Argument of the annotated fn.
+pub(crate) struct TypedArgument {
+    pub(crate) ident: Ident,
+    pub(crate) ty: Type,
+    pub(crate) php_name: String,
+}
+
+/// Collects the arguments of signature except the receiver, the PHP argument
+/// names are taken from the patterns, the others are named `argN`, as the
+/// typed handler of phper.
+pub(crate) fn typed_arguments(sig: &Signature) -> syn::Result<Vec<TypedArgument>> {
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "generic functions can't be registered to php",
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "async functions can't be registered to php",
+        ));
+    }
+
+    sig.inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(pat_type),
+        })
+        .enumerate()
+        .map(|(i, pat_type)| {
+            let php_name = match &*pat_type.pat {
+                Pat::Ident(pat_ident) => pat_ident
+                    .ident
+                    .to_string()
+                    .trim_start_matches("r#")
+                    .trim_start_matches('_')
+                    .to_owned(),
+                _ => String::new(),
+            };
+            let php_name = if php_name.is_empty() {
+                format!("arg{}", i + 1)
+            } else {
+                php_name
+            };
+            Ok(TypedArgument {
+                ident: Ident::new(&format!("__phper_arg{}", i), Span::mixed_site()),
+                ty: (*pat_type.ty).clone(),
+                php_name,
+            })
+        })
+        .collect()
+}
+
+/// Generates the closure calling `func`.
+fn typed_handler(sig: &Signature, func: TokenStream2, arguments: &[TypedArgument]) -> TokenStream2 {
+    let idents = arguments
+        .iter()
+        .map(|argument| &argument.ident)
+        .collect::<Vec<_>>();
+    let tys = arguments.iter().map(|argument| &argument.ty);
+    let body = wrap_result(&sig.output, quote! { #func(#(#idents),*) });
+
+    quote! {
+        |#(#idents: #tys),*| #body
+    }
+}
+
+/// Wraps the returned value of `call` into `Result` if it isn't.
+pub(crate) fn wrap_result(output: &ReturnType, call: TokenStream2) -> TokenStream2 {
+    match output {
+        ReturnType::Type(_, ty) if is_result_type(ty) => call,
+        ReturnType::Type(..) | ReturnType::Default => {
+            quote! { ::std::result::Result::Ok::<_, ::std::convert::Infallible>(#call) }
+        }
+    }
+}
+
+/// Detects if the type is `Result` or `phper::Result`, by the last segment of
+/// path.
+pub(crate) fn is_result_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Result"),
+        _ => false,
+    }
+}
+
+pub(crate) fn doc_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect()
+}
@@ -16,7 +16,9 @@
 // classes, like `cxx`.
 
 mod alloc;
+mod classes;
 mod derives;
+mod functions;
 mod globals;
 mod inner;
 mod log;
@@ -76,3 +78,119 @@ pub fn c_str_ptr(input: TokenStream) -> TokenStream {
 pub fn php_get_module(attr: TokenStream, input: TokenStream) -> TokenStream {
     inner::php_get_module(attr, input)
 }
+
+/// Register the Rust fn as PHP function.
+///
+/// Generates the `<fn>_entity()` function returning the
+/// `phper::functions::FunctionEntity`, which can be registered by
+/// `Module::add_function_entity`. The argument infos, type declarations and
+/// return type are inferred from the signature, the PHP argument names are
+/// taken from the Rust argument names. The doc comments are copied to the
+/// generated function.
+///
+/// The arguments should implement `phper::values::TypedArgument`, and the
+/// returned value should implement `phper::values::IntoZVal`, or be a `Result`
+/// of it, whose error implements `phper::errors::Throwable`.
+///
+/// The PHP function name can be renamed by `#[php_function(name = "...")]`.
+///
+/// # Examples
+///
+/// ```no_test
+/// use phper::{modules::Module, php_function, php_get_module};
+///
+/// /// Say hello to someone.
+/// #[php_function]
+/// fn say_hello(name: &str, times: Option<i64>) -> phper::Result<String> {
+///     Ok(format!("Hello, {}!\n", name).repeat(times.unwrap_or(1) as usize))
+/// }
+///
+/// #[php_get_module]
+/// pub fn get_module() -> Module {
+///     let mut module = Module::new(
+///         env!("CARGO_CRATE_NAME"),
+///         env!("CARGO_PKG_VERSION"),
+///         env!("CARGO_PKG_AUTHORS"),
+///     );
+///
+///     module.add_function_entity(say_hello_entity());
+///
+///     module
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn php_function(attr: TokenStream, input: TokenStream) -> TokenStream {
+    functions::php_function(attr, input)
+}
+
+/// Register the Rust struct as PHP class, the struct becomes the state of
+/// object, so it should implement `Default`, unless it is built by the
+/// constructor of [`macro@php_impl`].
+///
+/// Implements `phper::classes::PhpClass` for the struct, whose `class_entity()`
+/// builds the `phper::classes::entity::ClassEntity` bound to the generated
+/// `phper::classes::StaticStateClass`, so the impl block of the struct should
+/// be annotated by [`macro@php_impl`] too.
+///
+/// The attribute accepts:
+///
+/// - `name = "..."`: The PHP class name, defaults to the struct name.
+/// - `property(name = "...", default = ...)`: Declare the PHP property, with
+///   the optional flags `public` (default), `protected`, `private` and
+///   `static`. The property is stored by the PHP object, not the state.
+///
+/// # Examples
+///
+/// ```no_test
+/// use phper::{classes::PhpClass, php_class, php_impl};
+///
+/// #[php_class(name = "Demo\\Counter", property(name = "label", default = "counter"))]
+/// pub struct Counter {
+///     count: i64,
+/// }
+///
+/// #[php_impl]
+/// impl Counter {
+///     #[php(constructor)]
+///     pub fn new(start: i64) -> Self {
+///         Self { count: start }
+///     }
+///
+///     pub fn increase(&mut self) -> i64 {
+///         self.count += 1;
+///         self.count
+///     }
+///
+///     #[php(name = "getCount")]
+///     pub fn count(&self) -> i64 {
+///         self.count
+///     }
+/// }
+///
+/// // In `get_module`.
+/// module.add_class(Counter::class_entity());
+/// ```
+#[proc_macro_attribute]
+pub fn php_class(attr: TokenStream, input: TokenStream) -> TokenStream {
+    classes::php_class(attr, input)
+}
+
+/// Register the methods in the impl block of [`macro@php_class`] struct.
+///
+/// The methods receiving `&self` or `&mut self` become the PHP methods, whose
+/// state is passed as the receiver, and the others become the static methods.
+/// The arguments and return types are inferred as
+/// [`macro@php_function`].
+///
+/// The methods accept the attribute `#[php(...)]`:
+///
+/// - `name = "..."`: The PHP method name, defaults to the Rust method name.
+/// - `public` (default), `protected` or `private`: The visibility.
+/// - `constructor`: Register as `__construct`, the method should return `Self`
+///   or `Result<Self, E>`, which builds the state, so the struct doesn't need
+///   `Default`. The object isn't initialized until the constructor called, see
+///   `phper::classes::entity::ClassEntity::new_with_typed_constructor`.
+#[proc_macro_attribute]
+pub fn php_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    classes::php_impl(attr, input)
+}
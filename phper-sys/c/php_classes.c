@@ -19,3 +19,159 @@ phper_register_class_entry(zend_class_entry *ce, zend_class_entry *parent,
 
     return zend_register_internal_class_ex(ce, parent);
 }
+
+bool phper_class_entry_set_readonly(zend_class_entry *ce) {
+#if PHP_VERSION_ID >= 80200
+    ce->ce_flags |= ZEND_ACC_READONLY_CLASS | ZEND_ACC_NO_DYNAMIC_PROPERTIES;
+    return true;
+#else
+    return false;
+#endif
+}
+
+// Builds the type of class members, the class names joined by `|` are
+// converted into persistent interned strings, which live with the class.
+static zend_type phper_persistent_type(uint32_t type_mask,
+                                       const char *class_name) {
+    const char *start = class_name;
+    uint32_t num_types = 1;
+    uint32_t i = 0;
+    zend_type_list *list;
+    zend_type type;
+
+    if (class_name == NULL) {
+        return (zend_type)ZEND_TYPE_INIT_MASK(type_mask);
+    }
+
+    while ((start = strchr(start, '|')) != NULL) {
+        num_types++;
+        start++;
+    }
+
+    if (num_types == 1) {
+        zend_string *name =
+            zend_string_init_interned(class_name, strlen(class_name), 1);
+        return (zend_type)ZEND_TYPE_INIT_CLASS(name, 0, type_mask);
+    }
+
+    list = pemalloc(ZEND_TYPE_LIST_SIZE(num_types), 1);
+    list->num_types = num_types;
+    start = class_name;
+    while (true) {
+        const char *end = strchr(start, '|');
+        size_t len = end != NULL ? (size_t)(end - start) : strlen(start);
+        zend_string *name = zend_string_init_interned(start, len, 1);
+        list->types[i++] = (zend_type)ZEND_TYPE_INIT_CLASS(name, 0, 0);
+        if (end == NULL) {
+            break;
+        }
+        start = end + 1;
+    }
+    type = (zend_type)ZEND_TYPE_INIT_MASK(type_mask);
+    ZEND_TYPE_SET_LIST(type, list);
+    ZEND_TYPE_FULL_MASK(type) |= _ZEND_TYPE_UNION_BIT;
+    return type;
+}
+
+// Copies the value of class members, the string is converted into persistent
+// interned string, and the array is converted into persistent immutable array,
+// since the values of internal class should be persistent.
+static void phper_persistent_value(zval *dest, const zval *value) {
+    HashTable *src, *ht;
+    zend_string *key;
+    zend_ulong index;
+    zval *val;
+
+    switch (Z_TYPE_P(value)) {
+    case IS_STRING:
+        ZVAL_INTERNED_STR(dest, zend_string_init_interned(Z_STRVAL_P(value),
+                                                          Z_STRLEN_P(value), 1));
+        return;
+
+    case IS_ARRAY:
+        src = Z_ARRVAL_P(value);
+        if (zend_hash_num_elements(src) == 0) {
+            ZVAL_EMPTY_ARRAY(dest);
+            return;
+        }
+
+        ht = pemalloc(sizeof(HashTable), 1);
+        zend_hash_init(ht, zend_hash_num_elements(src), NULL, NULL, 1);
+        ZEND_HASH_FOREACH_KEY_VAL(src, index, key, val) {
+            zval item;
+            phper_persistent_value(&item, val);
+            if (key != NULL) {
+                zend_hash_add_new(ht,
+                                  zend_string_init_interned(ZSTR_VAL(key),
+                                                            ZSTR_LEN(key), 1),
+                                  &item);
+            } else {
+                zend_hash_index_add_new(ht, index, &item);
+            }
+        }
+        ZEND_HASH_FOREACH_END();
+
+        // Mark as immutable, so it's copied without refcounting, and separated
+        // before modifying.
+        GC_SET_REFCOUNT(ht, 2);
+        GC_ADD_FLAGS(ht, IS_ARRAY_IMMUTABLE);
+        ZVAL_ARR(dest, ht);
+        Z_TYPE_FLAGS_P(dest) = 0;
+        return;
+
+    default:
+        ZVAL_COPY_VALUE(dest, value);
+    }
+}
+
+bool phper_zend_declare_class_constant(zend_class_entry *ce, const char *name,
+                                       size_t name_len, const zval *value,
+                                       uint32_t flags, uint32_t type_mask,
+                                       const char *class_name) {
+    zend_string *key = zend_string_init_interned(name, name_len, 1);
+    zval constant;
+
+    phper_persistent_value(&constant, value);
+
+#if PHP_VERSION_ID >= 80300
+    zend_declare_typed_class_constant(
+        ce, key, &constant, flags, NULL,
+        phper_persistent_type(type_mask, class_name));
+#else
+    if (type_mask != 0 || class_name != NULL) {
+        zend_string_release(key);
+        return false;
+    }
+    zend_declare_class_constant_ex(ce, key, &constant, flags, NULL);
+#endif
+
+    zend_string_release(key);
+    return true;
+}
+
+void phper_zend_declare_typed_property(zend_class_entry *ce, const char *name,
+                                       size_t name_len, const zval *value,
+                                       uint32_t flags, uint32_t type_mask,
+                                       const char *class_name) {
+    zend_string *key = zend_string_init_interned(name, name_len, 1);
+    zval property;
+
+    // The typed property without default value is uninitialized.
+    if (value == NULL) {
+        ZVAL_UNDEF(&property);
+    } else {
+        phper_persistent_value(&property, value);
+    }
+
+    zend_declare_typed_property(ce, key, &property, flags, NULL,
+                                phper_persistent_type(type_mask, class_name));
+    zend_string_release(key);
+}
+
+const zval *phper_zend_get_class_constant_value(zend_class_entry *ce,
+                                                const char *name,
+                                                size_t name_len) {
+    zend_class_constant *c =
+        zend_hash_str_find_ptr(CE_CONSTANTS_TABLE(ce), name, name_len);
+    return c != NULL ? &c->value : NULL;
+}
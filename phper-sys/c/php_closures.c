@@ -0,0 +1,196 @@
+#include <phper.h>
+
+#include <zend_closures.h>
+
+#if PHP_VERSION_ID >= 80300
+#define PHPER_TYPE_HAS_LITERAL_NAME(t) ZEND_TYPE_HAS_LITERAL_NAME(t)
+#else
+#define PHPER_TYPE_HAS_LITERAL_NAME(t) ZEND_TYPE_HAS_NAME(t)
+#endif
+
+static zend_object_handlers phper_closure_handlers;
+static void (*phper_closure_origin_free_obj)(zend_object *object) = NULL;
+static zend_object *(*phper_closure_origin_clone_obj)(zend_object *object) =
+    NULL;
+static zif_handler phper_closure_origin_bind = NULL;
+static zif_handler phper_closure_origin_bind_to = NULL;
+static void (*phper_closure_addref_data)(void *data) = NULL;
+static void (*phper_closure_release_data)(void *data) = NULL;
+
+// Converts the literal class names of internal arg info into zend strings, as
+// `zend_register_functions` does, the type list is released with the data.
+static void phper_convert_literal_class_name(zend_type *type) {
+    const char *class_name = ZEND_TYPE_LITERAL_NAME(*type);
+    const char *start = class_name;
+    uint32_t num_types = 1;
+    uint32_t i = 0;
+    zend_type_list *list;
+
+    while ((start = strchr(start, '|')) != NULL) {
+        num_types++;
+        start++;
+    }
+
+    if (num_types == 1) {
+        zend_string *name =
+            zend_string_init_interned(class_name, strlen(class_name), 0);
+        ZEND_TYPE_SET_PTR_AND_KIND(*type, name, _ZEND_TYPE_NAME_BIT);
+        return;
+    }
+
+    list = emalloc(ZEND_TYPE_LIST_SIZE(num_types));
+    list->num_types = num_types;
+    start = class_name;
+    while (true) {
+        const char *end = strchr(start, '|');
+        size_t len = end != NULL ? (size_t)(end - start) : strlen(start);
+        zend_string *name = zend_string_init_interned(start, len, 0);
+        list->types[i++] = (zend_type)ZEND_TYPE_INIT_CLASS(name, 0, 0);
+        if (end == NULL) {
+            break;
+        }
+        start = end + 1;
+    }
+    ZEND_TYPE_SET_LIST(*type, list);
+    ZEND_TYPE_FULL_MASK(*type) |= _ZEND_TYPE_UNION_BIT;
+}
+
+static const zend_internal_arg_info *
+phper_closure_arg_info(zend_object *object) {
+    return zend_get_closure_method_def(object)->internal_function.arg_info;
+}
+
+static void *phper_closure_data(zend_object *object) {
+    return (void *)(phper_closure_arg_info(object) - 1)->default_value;
+}
+
+// The closures copied by `Closure::bind`, `Closure::bindTo` and cloning share
+// the function of origin one, so they take the handlers and a reference of
+// the data.
+static void phper_closure_track_copy(zend_object *origin, zval *copy) {
+    if (origin->handlers != &phper_closure_handlers ||
+        Z_TYPE_P(copy) != IS_OBJECT || Z_OBJ_P(copy) == origin ||
+        phper_closure_arg_info(Z_OBJ_P(copy)) !=
+            phper_closure_arg_info(origin)) {
+        return;
+    }
+    Z_OBJ_P(copy)->handlers = &phper_closure_handlers;
+    phper_closure_addref_data(phper_closure_data(origin));
+}
+
+static void phper_closure_free_obj(zend_object *object) {
+    void *data = phper_closure_data(object);
+
+    phper_closure_origin_free_obj(object);
+    phper_closure_release_data(data);
+}
+
+static zend_object *phper_closure_clone_obj(zend_object *object) {
+    zend_object *clone = phper_closure_origin_clone_obj(object);
+    zval copy;
+
+    if (clone != NULL) {
+        ZVAL_OBJ(&copy, clone);
+        phper_closure_track_copy(object, &copy);
+    }
+    return clone;
+}
+
+static ZEND_NAMED_FUNCTION(phper_closure_bind) {
+    zval *closure = ZEND_CALL_NUM_ARGS(execute_data) > 0
+                        ? ZEND_CALL_ARG(execute_data, 1)
+                        : NULL;
+
+    phper_closure_origin_bind(INTERNAL_FUNCTION_PARAM_PASSTHRU);
+    if (closure != NULL && Z_TYPE_P(closure) == IS_OBJECT) {
+        phper_closure_track_copy(Z_OBJ_P(closure), return_value);
+    }
+}
+
+static ZEND_NAMED_FUNCTION(phper_closure_bind_to) {
+    phper_closure_origin_bind_to(INTERNAL_FUNCTION_PARAM_PASSTHRU);
+    phper_closure_track_copy(Z_OBJ_P(ZEND_THIS), return_value);
+}
+
+static zif_handler phper_closure_replace_method(const char *name,
+                                                zif_handler handler) {
+    zend_function *func = zend_hash_str_find_ptr(
+        &zend_ce_closure->function_table, name, strlen(name));
+    zif_handler origin = func->internal_function.handler;
+
+    func->internal_function.handler = handler;
+    return origin;
+}
+
+void phper_zend_closures_startup(void (*addref_data)(void *data),
+                                 void (*release_data)(void *data)) {
+    phper_closure_addref_data = addref_data;
+    phper_closure_release_data = release_data;
+    // Replaced at the module startup, before the scripts are compiled, because
+    // the JIT may call the handlers directly.
+    phper_closure_origin_bind =
+        phper_closure_replace_method("bind", phper_closure_bind);
+    phper_closure_origin_bind_to =
+        phper_closure_replace_method("bindto", phper_closure_bind_to);
+}
+
+void phper_zend_closures_shutdown(void) {
+    phper_closure_replace_method("bind", phper_closure_origin_bind);
+    phper_closure_replace_method("bindto", phper_closure_origin_bind_to);
+}
+
+void phper_zend_arg_info_release_type(zend_internal_arg_info *arg_info) {
+    if (ZEND_TYPE_HAS_LIST(arg_info->type)) {
+        efree(ZEND_TYPE_LIST(arg_info->type));
+    }
+}
+
+void phper_zend_create_closure(zval *result, zend_internal_arg_info *arg_info,
+                               uint32_t num_args, zif_handler handler) {
+    zend_internal_function func;
+    uint32_t i;
+
+    memset(&func, 0, sizeof(func));
+    func.type = ZEND_INTERNAL_FUNCTION;
+    func.function_name = zend_string_init("{closure}", strlen("{closure}"), 0);
+    func.handler = handler;
+    func.arg_info = arg_info + 1;
+    func.num_args = num_args;
+    func.required_num_args = (uint32_t)(uintptr_t)arg_info[0].name;
+
+    if (num_args > 0 && ZEND_ARG_IS_VARIADIC(&arg_info[num_args])) {
+        func.fn_flags |= ZEND_ACC_VARIADIC;
+        func.num_args--;
+    }
+    if (ZEND_TYPE_IS_SET(arg_info[0].type)) {
+        func.fn_flags |= ZEND_ACC_HAS_RETURN_TYPE;
+    }
+    for (i = 0; i <= num_args; i++) {
+        if (i > 0 && ZEND_TYPE_IS_SET(arg_info[i].type)) {
+            func.fn_flags |= ZEND_ACC_HAS_TYPE_HINTS;
+        }
+        if (PHPER_TYPE_HAS_LITERAL_NAME(arg_info[i].type)) {
+            phper_convert_literal_class_name(&arg_info[i].type);
+        }
+    }
+
+#if PHP_VERSION_ID >= 80200
+    ZEND_MAP_PTR_INIT(func.run_time_cache,
+                      zend_arena_calloc(&CG(arena), 1,
+                                        zend_internal_run_time_cache_reserved_size()));
+#endif
+
+    zend_create_closure(result, (zend_function *)&func, NULL, NULL, NULL);
+    // The closure holds its own reference of function name.
+    zend_string_release(func.function_name);
+
+    if (phper_closure_origin_free_obj == NULL) {
+        memcpy(&phper_closure_handlers, Z_OBJ_HT_P(result),
+               sizeof(zend_object_handlers));
+        phper_closure_origin_free_obj = phper_closure_handlers.free_obj;
+        phper_closure_origin_clone_obj = phper_closure_handlers.clone_obj;
+        phper_closure_handlers.free_obj = phper_closure_free_obj;
+        phper_closure_handlers.clone_obj = phper_closure_clone_obj;
+    }
+    Z_OBJ_P(result)->handlers = &phper_closure_handlers;
+}
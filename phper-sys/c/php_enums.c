@@ -0,0 +1,85 @@
+#include <phper.h>
+
+#if PHP_VERSION_ID >= 80100
+#include <zend_enum.h>
+#endif
+
+zend_class_entry *
+phper_register_enum_entry(const char *enum_name, uint32_t backing_type,
+                          const zend_function_entry *functions) {
+#if PHP_VERSION_ID >= 80100
+    return zend_register_internal_enum(enum_name, (zend_uchar)backing_type,
+                                       functions);
+#else
+    return NULL;
+#endif
+}
+
+void phper_enum_add_case(zend_class_entry *ce, const char *case_name,
+                         size_t case_name_len, const zval *value) {
+#if PHP_VERSION_ID >= 80100
+    zend_string *name = zend_string_init_interned(case_name, case_name_len, 1);
+    zval case_value;
+
+    if (value == NULL) {
+        zend_enum_add_case(ce, name, NULL);
+    } else if (Z_TYPE_P(value) == IS_STRING) {
+        // The backing value of internal enum should be persistent.
+        ZVAL_INTERNED_STR(&case_value,
+                          zend_string_init_interned(Z_STRVAL_P(value),
+                                                    Z_STRLEN_P(value), 1));
+        zend_enum_add_case(ce, name, &case_value);
+    } else {
+        ZVAL_COPY_VALUE(&case_value, value);
+        zend_enum_add_case(ce, name, &case_value);
+    }
+
+    zend_string_release(name);
+#endif
+}
+
+zend_object *phper_enum_get_case(zend_class_entry *ce, const char *case_name,
+                                 size_t case_name_len) {
+#if PHP_VERSION_ID >= 80100
+    zend_class_constant *c;
+
+    if (!(ce->ce_flags & ZEND_ACC_ENUM)) {
+        return NULL;
+    }
+    c = zend_hash_str_find_ptr(CE_CONSTANTS_TABLE(ce), case_name,
+                               case_name_len);
+    if (c == NULL || !(ZEND_CLASS_CONST_FLAGS(c) & ZEND_CLASS_CONST_IS_CASE)) {
+        return NULL;
+    }
+    if (Z_TYPE(c->value) == IS_CONSTANT_AST &&
+        zval_update_constant_ex(&c->value, c->ce) == FAILURE) {
+        return NULL;
+    }
+    return Z_OBJ(c->value);
+#else
+    return NULL;
+#endif
+}
+
+const zval *phper_enum_fetch_case_name(const zend_object *obj) {
+#if PHP_VERSION_ID >= 80100
+    if (!(obj->ce->ce_flags & ZEND_ACC_ENUM)) {
+        return NULL;
+    }
+    return zend_enum_fetch_case_name((zend_object *)obj);
+#else
+    return NULL;
+#endif
+}
+
+const zval *phper_enum_fetch_case_value(const zend_object *obj) {
+#if PHP_VERSION_ID >= 80100
+    if (!(obj->ce->ce_flags & ZEND_ACC_ENUM) ||
+        obj->ce->enum_backing_type == IS_UNDEF) {
+        return NULL;
+    }
+    return zend_enum_fetch_case_value((zend_object *)obj);
+#else
+    return NULL;
+#endif
+}
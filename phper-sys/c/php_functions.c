@@ -8,14 +8,105 @@ zend_string *phper_get_function_name(const zend_function *func) {
     return func->common.function_name;
 }
 
+// Calls the function with the resolved `fcc` if it isn't NULL. The positional
+// arguments should be sent by reference are wrapped into references before
+// calling, and unwrapped after calling, so the outputs are written back.
+static bool phper_call_function(zval *function_name, zend_object *object,
+                                zend_fcall_info_cache *fcc, zval *retval_ptr,
+                                zval *params, uint32_t param_count,
+                                HashTable *named_params) {
+    zend_fcall_info fci;
+    bool *made_refs = NULL;
+    bool result;
+    uint32_t i;
+
+    if (fcc != NULL) {
+        for (i = 0; i < param_count; i++) {
+            if (ARG_SHOULD_BE_SENT_BY_REF(fcc->function_handler, i + 1) &&
+                !Z_ISREF(params[i])) {
+                if (made_refs == NULL) {
+                    made_refs = ecalloc(param_count, sizeof(bool));
+                }
+                ZVAL_MAKE_REF(&params[i]);
+                made_refs[i] = true;
+            }
+        }
+    }
+
+    fci.size = sizeof(fci);
+    ZVAL_COPY_VALUE(&fci.function_name, function_name);
+    fci.object = object;
+    fci.retval = retval_ptr;
+    fci.params = params;
+    fci.param_count = param_count;
+    fci.named_params = named_params;
+
+    result = zend_call_function(&fci, fcc) == SUCCESS;
+
+    if (made_refs != NULL) {
+        for (i = 0; i < param_count; i++) {
+            if (made_refs[i]) {
+                zval value;
+                ZVAL_COPY(&value, Z_REFVAL(params[i]));
+                zval_ptr_dtor(&params[i]);
+                ZVAL_COPY_VALUE(&params[i], &value);
+            }
+        }
+        efree(made_refs);
+    }
+
+    return result;
+}
+
 bool phper_call_user_function(zval *object, zval *function_name,
-                              zval *retval_ptr, const zval *params,
-                              uint32_t param_count,
-                              const HashTable *named_params) {
+                              zval *retval_ptr, zval *params,
+                              uint32_t param_count, HashTable *named_params) {
+    zend_fcall_info_cache fcc;
+    zend_object *obj = object != NULL ? Z_OBJ_P(object) : NULL;
+
+    if (!zend_is_callable_ex(function_name, obj, 0, NULL, &fcc, NULL)) {
+        // Resolve again in calling, to throw the error of invalid callback.
+        return phper_call_function(function_name, obj, NULL, retval_ptr, params,
+                                   param_count, named_params);
+    }
+    return phper_call_function(function_name, obj, &fcc, retval_ptr, params,
+                               param_count, named_params);
+}
+
+bool phper_zend_is_callable_ex(zval *callable, zend_fcall_info_cache *fcc,
+                               zend_string **error) {
+    char *error_str = NULL;
+    bool result = zend_is_callable_ex(callable, NULL, 0, NULL, fcc, &error_str);
+
+    if (error_str != NULL) {
+        if (!result) {
+            *error = zend_string_init(error_str, strlen(error_str), 0);
+        }
+        efree(error_str);
+    }
 
-    _call_user_function_impl(object, function_name, retval_ptr, param_count,
-                             (zval *)params,
-                             (HashTable *)named_params) == SUCCESS;
+    // The trampoline of `__call` and `__callStatic` is freed after calling, so
+    // it can't be cached, and will be resolved in every calling.
+    if (result && (fcc->function_handler->common.fn_flags &
+                   ZEND_ACC_CALL_VIA_TRAMPOLINE)) {
+        zend_release_fcall_info_cache(fcc);
+        fcc->function_handler = NULL;
+    }
+
+    return result;
+}
+
+bool phper_zend_call_function_with_cache(zval *callable,
+                                         zend_fcall_info_cache *fcc,
+                                         zval *retval_ptr, zval *params,
+                                         uint32_t param_count,
+                                         HashTable *named_params) {
+    if (fcc->function_handler == NULL) {
+        return phper_call_user_function(NULL, callable, retval_ptr, params,
+                                        param_count, named_params);
+    }
+    return phper_call_function(callable, NULL, fcc, retval_ptr, params,
+                               param_count, named_params);
 }
 
 const zval *phper_zend_call_var_num(const zend_execute_data *execute_data,
@@ -32,7 +123,146 @@ uint32_t phper_zend_num_args(const zend_execute_data *execute_data) {
     return ZEND_NUM_ARGS();
 }
 
+zend_array *
+phper_zend_call_extra_named_params(const zend_execute_data *execute_data) {
+    if (ZEND_CALL_INFO(execute_data) & ZEND_CALL_HAS_EXTRA_NAMED_PARAMS) {
+        return execute_data->extra_named_params;
+    }
+    return NULL;
+}
+
 bool phper_zend_get_parameters_array_ex(uint32_t param_count,
                                         zval *argument_array) {
     return zend_get_parameters_array_ex(param_count, argument_array) == SUCCESS;
 }
+
+static bool phper_check_class_type(zend_type type, zval *value) {
+    zend_type *list_type;
+
+    if (Z_TYPE_P(value) != IS_OBJECT || !ZEND_TYPE_IS_COMPLEX(type)) {
+        return false;
+    }
+
+    ZEND_TYPE_FOREACH(type, list_type) {
+        if (ZEND_TYPE_HAS_NAME(*list_type)) {
+            zend_class_entry *ce = zend_lookup_class_ex(
+                ZEND_TYPE_NAME(*list_type), NULL, ZEND_FETCH_CLASS_NO_AUTOLOAD);
+            if (ce != NULL && instanceof_function(Z_OBJCE_P(value), ce)) {
+                return true;
+            }
+        }
+    }
+    ZEND_TYPE_FOREACH_END();
+
+    return false;
+}
+
+static bool phper_verify_arg_type(const zend_arg_info *arg_info, zval *arg) {
+    zend_type type = arg_info->type;
+    uint32_t type_mask;
+
+    if (!ZEND_TYPE_IS_SET(type)) {
+        return true;
+    }
+
+    ZVAL_DEREF(arg);
+    if (Z_TYPE_P(arg) == IS_UNDEF ||
+        ZEND_TYPE_CONTAINS_CODE(type, Z_TYPE_P(arg))) {
+        return true;
+    }
+
+    if (phper_check_class_type(type, arg)) {
+        return true;
+    }
+
+    type_mask = ZEND_TYPE_FULL_MASK(type);
+
+    if ((type_mask & MAY_BE_CALLABLE) && zend_is_callable(arg, 0, NULL)) {
+        return true;
+    }
+
+#if PHP_VERSION_ID < 80200
+    if ((type_mask & MAY_BE_ITERABLE) && zend_is_iterable(arg)) {
+        return true;
+    }
+#endif
+
+    // Null to scalar is not allowed, as the userland functions.
+    return zend_verify_scalar_type_hint(type_mask, arg,
+                                        ZEND_ARG_USES_STRICT_TYPES(), false);
+}
+
+bool phper_zend_verify_arg_types(zend_execute_data *execute_data) {
+    zend_function *func = execute_data->func;
+    uint32_t num_args = ZEND_CALL_NUM_ARGS(execute_data);
+    uint32_t i;
+
+    if (!(func->common.fn_flags & ZEND_ACC_HAS_TYPE_HINTS)) {
+        return true;
+    }
+
+    for (i = 0; i < num_args; i++) {
+        zend_arg_info *arg_info;
+        zval *arg = ZEND_CALL_ARG(execute_data, i + 1);
+
+        if (i < func->common.num_args) {
+            arg_info = &func->common.arg_info[i];
+        } else if (func->common.fn_flags & ZEND_ACC_VARIADIC) {
+            arg_info = &func->common.arg_info[func->common.num_args];
+        } else {
+            break;
+        }
+
+        if (!phper_verify_arg_type(arg_info, arg)) {
+            zend_verify_arg_error(func, arg_info, i + 1, arg);
+            return false;
+        }
+    }
+
+    return true;
+}
+
+bool phper_zend_verify_return_type(zend_execute_data *execute_data, zval *ret) {
+    zend_function *func = execute_data->func;
+    zend_type type;
+    uint32_t type_mask;
+
+    if (!(func->common.fn_flags & ZEND_ACC_HAS_RETURN_TYPE) || EG(exception)) {
+        return true;
+    }
+
+    type = (func->common.arg_info - 1)->type;
+    type_mask = ZEND_TYPE_FULL_MASK(type);
+    ZVAL_DEREF(ret);
+
+    if (type_mask & MAY_BE_NEVER) {
+        zend_verify_never_error(func);
+        return false;
+    }
+
+    if (type_mask & MAY_BE_VOID) {
+        if (Z_TYPE_P(ret) == IS_NULL) {
+            return true;
+        }
+    } else if (ZEND_TYPE_CONTAINS_CODE(type, Z_TYPE_P(ret))) {
+        return true;
+    } else if (phper_check_class_type(type, ret)) {
+        return true;
+    } else if ((type_mask & MAY_BE_STATIC) && Z_TYPE_P(ret) == IS_OBJECT) {
+        zend_class_entry *called_scope = zend_get_called_scope(execute_data);
+        if (called_scope != NULL &&
+            instanceof_function(Z_OBJCE_P(ret), called_scope)) {
+            return true;
+        }
+    } else if ((type_mask & MAY_BE_CALLABLE) && zend_is_callable(ret, 0, NULL)) {
+        return true;
+    }
+#if PHP_VERSION_ID < 80200
+    else if ((type_mask & MAY_BE_ITERABLE) && zend_is_iterable(ret)) {
+        return true;
+    }
+#endif
+
+    zend_verify_return_error(func, ret);
+    return false;
+}
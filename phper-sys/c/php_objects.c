@@ -22,4 +22,32 @@ void phper_zend_object_release(zend_object *obj) {
 
 uint32_t phper_zend_object_gc_refcount(const zend_object *obj) {
     return GC_REFCOUNT(obj);
-}
\ No newline at end of file
+}
+
+HashTable *phper_zend_std_get_gc_into(zend_object *obj,
+                                      zend_get_gc_buffer *gc_buffer) {
+    zval *table;
+    int n;
+    HashTable *ht = zend_std_get_gc(obj, &table, &n);
+    for (int i = 0; i < n; i++) {
+        zend_get_gc_buffer_add_zval(gc_buffer, &table[i]);
+    }
+    return ht;
+}
+
+void phper_zend_get_gc_buffer_add_zval(zend_get_gc_buffer *gc_buffer,
+                                       zval *zv) {
+    zend_get_gc_buffer_add_zval(gc_buffer, zv);
+}
+
+void phper_zend_get_gc_buffer_add_obj(zend_get_gc_buffer *gc_buffer,
+                                      zend_object *obj) {
+    zval zv;
+    ZVAL_OBJ(&zv, obj);
+    zend_get_gc_buffer_add_zval(gc_buffer, &zv);
+}
+
+void phper_zend_get_gc_buffer_use(zend_get_gc_buffer *gc_buffer, zval **table,
+                                  int *n) {
+    zend_get_gc_buffer_use(gc_buffer, table, n);
+}
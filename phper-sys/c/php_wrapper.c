@@ -77,22 +77,52 @@ phper_zend_begin_arg_info_ex(bool return_reference,
 #undef const
 }
 
+zend_internal_arg_info phper_zend_begin_arg_with_return_type_info_ex(
+    bool return_reference, uintptr_t required_num_args, uint32_t type_mask,
+    const char *class_name) {
+    return phper_zend_arg_info_with_type(return_reference, false,
+                                         (const char *)required_num_args,
+                                         type_mask, class_name);
+}
+
 zend_internal_arg_info phper_zend_arg_info(bool pass_by_ref, const char *name) {
     zend_internal_arg_info info[] = {ZEND_ARG_INFO(pass_by_ref, )};
     info[0].name = name;
     return info[0];
 }
 
-//  zend_internal_arg_info phper_zend_arg_info(
-//     const char *name, int32_t type, bool return_reference, bool is_variadic,
-//     bool is_tentative, uintptr_t required_num_args) {
-//     zend_internal_arg_info info = {
-//         .name = name,
-//         .type = ZEND_TYPE_INIT_MASK(
-//             type |
-//             _ZEND_ARG_INFO_FLAGS(return_reference, is_variadic,
-//             is_tentative)),
-//     };
-
-//     return info;
-// }
+zend_internal_arg_info phper_zend_arg_info_with_type(bool pass_by_ref,
+                                                     bool is_variadic,
+                                                     const char *name,
+                                                     uint32_t type_mask,
+                                                     const char *class_name) {
+    uint32_t flags =
+        type_mask | _ZEND_ARG_INFO_FLAGS(pass_by_ref, is_variadic, 0);
+    zend_internal_arg_info info = {
+        .name = name,
+        .type = ZEND_TYPE_INIT_NONE(0),
+        .default_value = NULL,
+    };
+    if (class_name != NULL) {
+        info.type =
+            (zend_type)ZEND_TYPE_INIT_CLASS_CONST_MASK(class_name, flags);
+    } else {
+        info.type = (zend_type)ZEND_TYPE_INIT_MASK(flags);
+    }
+    return info;
+}
+
+uint32_t phper_zend_type_mask_from_code(uint32_t code) {
+    zend_type type = (zend_type)ZEND_TYPE_INIT_CODE(code, 0, 0);
+    return ZEND_TYPE_FULL_MASK(type);
+}
+
+bool phper_zend_get_default_from_internal_arg_info(
+    zval *default_value_zval, const zend_internal_arg_info *arg_info) {
+    if (arg_info->default_value == NULL) {
+        return false;
+    }
+    return zend_get_default_from_internal_arg_info(
+               default_value_zval, (zend_internal_arg_info *)arg_info) ==
+           SUCCESS;
+}
@@ -198,6 +198,14 @@ void *phper_zend_object_alloc(size_t obj_size, const zend_class_entry *ce);
 bool phper_object_init_ex(zval *arg, const zend_class_entry *class_type);
 void phper_zend_object_release(zend_object *obj);
 uint32_t phper_zend_object_gc_refcount(const zend_object *obj);
+HashTable *phper_zend_std_get_gc_into(zend_object *obj,
+                                      zend_get_gc_buffer *gc_buffer);
+void phper_zend_get_gc_buffer_add_zval(zend_get_gc_buffer *gc_buffer,
+                                       zval *zv);
+void phper_zend_get_gc_buffer_add_obj(zend_get_gc_buffer *gc_buffer,
+                                      zend_object *obj);
+void phper_zend_get_gc_buffer_use(zend_get_gc_buffer *gc_buffer, zval **table,
+                                  int *n);
 
 // ==================================================
 // class apis:
@@ -207,6 +215,20 @@ zend_class_entry phper_init_class_entry(const char *class_name,
 zend_class_entry *
 phper_register_class_entry(zend_class_entry *ce, zend_class_entry *parent,
                            const zend_function_entry *functions);
+bool phper_class_entry_set_readonly(zend_class_entry *ce);
+
+// ==================================================
+// enum apis:
+// ==================================================
+zend_class_entry *
+phper_register_enum_entry(const char *enum_name, uint32_t backing_type,
+                          const zend_function_entry *functions);
+void phper_enum_add_case(zend_class_entry *ce, const char *case_name,
+                         size_t case_name_len, const zval *value);
+zend_object *phper_enum_get_case(zend_class_entry *ce, const char *case_name,
+                                 size_t case_name_len);
+const zval *phper_enum_fetch_case_name(const zend_object *obj);
+const zval *phper_enum_fetch_case_value(const zend_object *obj);
 
 // ==================================================
 // interface apis:
@@ -217,22 +239,50 @@ zend_class_entry *
 phper_register_interface_entry(zend_class_entry *ce,
                                const zend_function_entry *functions);
 
+bool phper_zend_declare_class_constant(zend_class_entry *ce, const char *name,
+                                       size_t name_len, const zval *value,
+                                       uint32_t flags, uint32_t type_mask,
+                                       const char *class_name);
+
+void phper_zend_declare_typed_property(zend_class_entry *ce, const char *name,
+                                       size_t name_len, const zval *value,
+                                       uint32_t flags, uint32_t type_mask,
+                                       const char *class_name);
+
+const zval *phper_zend_get_class_constant_value(zend_class_entry *ce,
+                                                const char *name,
+                                                size_t name_len);
+
 bool phper_instanceof_function(const zend_class_entry *instance_ce,
                                const zend_class_entry *ce);
 
 zend_string *phper_get_function_or_method_name(const zend_function *func);
 zend_string *phper_get_function_name(const zend_function *func);
 bool phper_call_user_function(zval *object, zval *function_name,
-                              zval *retval_ptr, const zval *params,
-                              uint32_t param_count,
-                              const HashTable *named_params);
+                              zval *retval_ptr, zval *params,
+                              uint32_t param_count, HashTable *named_params);
+bool phper_zend_is_callable_ex(zval *callable, zend_fcall_info_cache *fcc,
+                               zend_string **error);
+bool phper_zend_call_function_with_cache(zval *callable,
+                                         zend_fcall_info_cache *fcc,
+                                         zval *retval_ptr, zval *params,
+                                         uint32_t param_count,
+                                         HashTable *named_params);
 const zval *phper_zend_call_var_num(const zend_execute_data *execute_data,
                                     int index);
 const zval *phper_zend_call_arg(const zend_execute_data *execute_data,
                                 int index);
 uint32_t phper_zend_num_args(const zend_execute_data *execute_data);
+zend_array *
+phper_zend_call_extra_named_params(const zend_execute_data *execute_data);
 bool phper_zend_get_parameters_array_ex(uint32_t param_count,
                                         zval *argument_array);
+void phper_zend_closures_startup(void (*addref_data)(void *data),
+                                 void (*release_data)(void *data));
+void phper_zend_closures_shutdown(void);
+void phper_zend_create_closure(zval *result, zend_internal_arg_info *arg_info,
+                               uint32_t num_args, zif_handler handler);
+void phper_zend_arg_info_release_type(zend_internal_arg_info *arg_info);
 
 // ==================================================
 // module apis:
@@ -260,7 +310,20 @@ const zend_resource *phper_register_persistent_find(const char *hash,
 zend_internal_arg_info
 phper_zend_begin_arg_info_ex(bool return_reference,
                              uintptr_t required_num_args);
+zend_internal_arg_info phper_zend_begin_arg_with_return_type_info_ex(
+    bool return_reference, uintptr_t required_num_args, uint32_t type_mask,
+    const char *class_name);
 zend_internal_arg_info phper_zend_arg_info(bool pass_by_ref, const char *name);
+zend_internal_arg_info phper_zend_arg_info_with_type(bool pass_by_ref,
+                                                     bool is_variadic,
+                                                     const char *name,
+                                                     uint32_t type_mask,
+                                                     const char *class_name);
+uint32_t phper_zend_type_mask_from_code(uint32_t code);
+bool phper_zend_get_default_from_internal_arg_info(
+    zval *default_value_zval, const zend_internal_arg_info *arg_info);
+bool phper_zend_verify_arg_types(zend_execute_data *execute_data);
+bool phper_zend_verify_return_type(zend_execute_data *execute_data, zval *ret);
 
 // ==================================================
 // Constants API:
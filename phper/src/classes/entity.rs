@@ -1,20 +1,42 @@
-use std::{any::Any, marker::PhantomData, mem::zeroed, ptr::null_mut, rc::Rc};
+use std::{
+    any::Any,
+    cmp::Ordering,
+    marker::PhantomData,
+    mem::{take, zeroed},
+    ptr::null_mut,
+    rc::Rc,
+};
 
 use phper_sys::{
-    phper_init_class_entry, phper_register_class_entry, zend_class_entry, zend_class_implements,
-    zend_function_entry,
+    phper_class_entry_set_readonly, phper_init_class_entry, phper_register_class_entry,
+    zend_class_entry, zend_class_implements, zend_function_entry, ZEND_ACC_EXPLICIT_ABSTRACT_CLASS,
+    ZEND_ACC_FINAL,
 };
 
 use crate::{
-    errors::Throwable,
-    functions::{Function, FunctionEntry, Method, MethodEntity},
-    objects::StateObj,
-    types::Scalar,
+    arrays::{ZArr, ZArray},
+    errors::{throw, Throwable},
+    functions::{
+        Argument, Function, FunctionEntry, Method, MethodEntity, TypedConstructorHandler,
+        TypedFunctionHandler, TypedMethodHandler, VariadicFunction, VariadicMethod,
+    },
+    objects::{StateObj, UninitState},
+    strings::ZStr,
+    types::{BuiltinType, Scalar, TypeHint, TypeInfo},
     values::ZVal,
 };
 
 use super::{
-    create_object, entry::ClassEntry, PropertyEntity, StateCloner, StateConstructor,
+    create_object,
+    entry::ClassEntry,
+    handlers::{
+        array_access_state, countable_state, get_iterator, ArrayAccess, ClassHandlers, Countable,
+        GcCollector, ObjectHooks, Operator, PropertyCheck, PropertyPurpose,
+    },
+    zend_classes::{
+        array_access_interface, countable_interface, stringable_interface, traversable_interface,
+    },
+    ConstantEntity, PropertyDefault, PropertyEntity, StateCloner, StateConstructor,
     StaticStateClass, Visibility,
 };
 
@@ -27,10 +49,15 @@ pub struct ClassEntity {
     state_constructor: Rc<StateConstructor>,
     method_entities: Vec<MethodEntity>,
     property_entities: Vec<PropertyEntity>,
+    constant_entities: Vec<ConstantEntity>,
     parent: Option<Box<dyn Fn() -> &'static ClassEntry>>,
     interfaces: Vec<Box<dyn Fn() -> &'static ClassEntry>>,
     bind_class: Option<&'static StaticStateClass>,
     state_cloner: Option<Rc<StateCloner>>,
+    built_by_constructor: bool,
+    readonly: bool,
+    stringable: bool,
+    hooks: ObjectHooks,
     _p: PhantomData<*mut ()>,
 }
 
@@ -84,14 +111,111 @@ impl ClassEntity {
             }),
             method_entities: Vec::new(),
             property_entities: Vec::new(),
+            constant_entities: Vec::new(),
             parent: None,
             interfaces: Vec::new(),
             state_cloner: None,
             bind_class: None,
+            built_by_constructor: false,
+            readonly: false,
+            stringable: false,
+            hooks: Default::default(),
             _p: Default::default(),
         }
     }
 
+    /// Construct a new `ClassEntity` with class name, and the constructor to
+    /// build state from the arguments of `__construct`, so the state type
+    /// doesn't need a default value.
+    ///
+    /// The constructor is registered as `__construct` with the argument infos.
+    /// Until the constructor called, like the object created by
+    /// `ReflectionClass::newInstanceWithoutConstructor`, `unserialize`, or the
+    /// userland subclass without calling `parent::__construct`, the object
+    /// isn't initialized, calling its methods throws `Error`, and the hooks
+    /// aren't called.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{classes::ClassEntity, functions::Argument};
+    ///
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// let class = ClassEntity::new_with_constructor(
+    ///     "Point",
+    ///     [Argument::by_val("x"), Argument::by_val("y")],
+    ///     |arguments| {
+    ///         Ok::<_, phper::Error>(Point {
+    ///             x: arguments[0].expect_long()?,
+    ///             y: arguments[1].expect_long()?,
+    ///         })
+    ///     },
+    /// );
+    /// ```
+    pub fn new_with_constructor<F, T, E>(
+        class_name: impl AsRef<str>,
+        arguments: impl IntoIterator<Item = Argument>,
+        constructor: F,
+    ) -> Self
+    where
+        F: Fn(&mut [ZVal]) -> Result<T, E> + 'static,
+        T: 'static,
+        E: Throwable + 'static,
+    {
+        let mut class = Self::new_with_state_constructor(class_name, || UninitState);
+        class.built_by_constructor = true;
+        class
+            .add_method("__construct", Visibility::Public, move |this, arguments| {
+                let state = constructor(arguments)?;
+                unsafe { ClassHandlers::initialize(this, Box::new(state)) };
+                Ok::<_, E>(())
+            })
+            .arguments(arguments);
+        class
+    }
+
+    /// Construct a new `ClassEntity` like
+    /// [`new_with_constructor`](Self::new_with_constructor), but the constructor
+    /// receives the typed arguments, which are inferred as
+    /// [`add_typed_method`](Self::add_typed_method) and renamed by
+    /// `argument_names`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::classes::ClassEntity;
+    ///
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// let class = ClassEntity::new_with_typed_constructor("Point", ["x", "y"], |x: i64, y: i64| {
+    ///     Ok::<_, phper::Error>(Point { x, y })
+    /// });
+    /// ```
+    pub fn new_with_typed_constructor<H, Args, S>(
+        class_name: impl AsRef<str>,
+        argument_names: impl IntoIterator<Item = S>,
+        constructor: H,
+    ) -> Self
+    where
+        H: TypedConstructorHandler<Args>,
+        Args: 'static,
+        S: AsRef<str>,
+    {
+        let mut class = Self::new_with_state_constructor(class_name, || UninitState);
+        class.built_by_constructor = true;
+        let mut constructor = MethodEntity::new_typed_constructor(constructor);
+        constructor.argument_names(argument_names);
+        class.method_entities.push(constructor);
+        class
+    }
+
     /// Add member method to class, with visibility and method handler.
     pub fn add_method<F, Z, E>(
         &mut self,
@@ -130,6 +254,82 @@ impl ClassEntity {
         self.method_entities.last_mut().unwrap()
     }
 
+    /// Add variadic member method to class, with visibility and method
+    /// handler, see
+    /// [`Module::add_variadic_function`](crate::modules::Module::add_variadic_function).
+    pub fn add_variadic_method<F, Z, E>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut StateObj, &mut [ZVal], &mut [ZVal], &ZArr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.method_entities.push(MethodEntity::new(
+            name,
+            Some(Rc::new(VariadicMethod::<F, Z, E>::new(handler))),
+            vis,
+        ));
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add variadic static method to class, with visibility and method
+    /// handler.
+    pub fn add_variadic_static_method<F, Z, E>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut [ZVal], &mut [ZVal], &ZArr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        let mut entity =
+            MethodEntity::new(name, Some(Rc::new(VariadicFunction::new(handler))), vis);
+        entity.set_vis_static();
+        self.method_entities.push(entity);
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add member method with typed arguments to class, the first argument of
+    /// handler is `&mut StateObj`, see
+    /// [`Module::add_typed_function`](crate::modules::Module::add_typed_function).
+    pub fn add_typed_method<H, Args>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: H,
+    ) -> &mut MethodEntity
+    where
+        H: TypedMethodHandler<Args>,
+        Args: 'static,
+    {
+        self.method_entities
+            .push(MethodEntity::new_typed(name, handler, vis));
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add static method with typed arguments to class.
+    pub fn add_typed_static_method<H, Args>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: H,
+    ) -> &mut MethodEntity
+    where
+        H: TypedFunctionHandler<Args>,
+        Args: 'static,
+    {
+        self.method_entities
+            .push(MethodEntity::new_typed_static(name, handler, vis));
+        self.method_entities.last_mut().unwrap()
+    }
+
     /// Add abstract method to class, with visibility (shouldn't be private).
     pub fn add_abstract_method(
         &mut self,
@@ -147,14 +347,21 @@ impl ClassEntity {
     /// The argument `value` should be `Copy` because 'zend_declare_property'
     /// receive only scalar zval , otherwise will report fatal error:
     /// "Internal zvals cannot be refcounted".
+    ///
+    /// For the array or constant expression default value, see
+    /// [PropertyEntity::default_value](super::PropertyEntity::default_value).
     pub fn add_property(
         &mut self,
         name: impl Into<String>,
         visibility: Visibility,
         value: impl Into<Scalar>,
-    ) {
-        self.property_entities
-            .push(PropertyEntity::new(name, visibility, value));
+    ) -> &mut PropertyEntity {
+        self.property_entities.push(PropertyEntity::new(
+            name,
+            visibility,
+            Some(PropertyDefault::Scalar(value.into())),
+        ));
+        self.property_entities.last_mut().unwrap()
     }
 
     /// Declare static property.
@@ -167,10 +374,75 @@ impl ClassEntity {
         name: impl Into<String>,
         visibility: Visibility,
         value: impl Into<Scalar>,
-    ) {
-        let mut entity = PropertyEntity::new(name, visibility, value);
+    ) -> &mut PropertyEntity {
+        let mut entity = PropertyEntity::new(
+            name,
+            visibility,
+            Some(PropertyDefault::Scalar(value.into())),
+        );
         entity.set_vis_static();
         self.property_entities.push(entity);
+        self.property_entities.last_mut().unwrap()
+    }
+
+    /// Declare typed property without default value, which is uninitialized
+    /// until assigned, like `public int $foo;` in PHP.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     classes::{ClassEntity, Visibility},
+    ///     types::{BuiltinType, TypeHint},
+    /// };
+    ///
+    /// let mut class = ClassEntity::new("Point");
+    /// class
+    ///     .add_typed_property("x", Visibility::Public, BuiltinType::Int)
+    ///     .set_readonly();
+    /// class.add_typed_property("label", Visibility::Public, TypeHint::from(BuiltinType::String).nullable());
+    /// class
+    ///     .add_property("count", Visibility::Public, 0)
+    ///     .type_hint(BuiltinType::Int);
+    /// ```
+    pub fn add_typed_property(
+        &mut self,
+        name: impl Into<String>,
+        visibility: Visibility,
+        type_hint: impl Into<TypeHint>,
+    ) -> &mut PropertyEntity {
+        let mut entity = PropertyEntity::new(name, visibility, None);
+        entity.type_hint(type_hint);
+        self.property_entities.push(entity);
+        self.property_entities.last_mut().unwrap()
+    }
+
+    /// Declare constant, with visibility.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     classes::{ClassEntity, Visibility},
+    ///     types::BuiltinType,
+    /// };
+    ///
+    /// let mut class = ClassEntity::new("Foo");
+    /// class.add_constant("BAR", 1, Visibility::Public);
+    /// class
+    ///     .add_constant("BAZ", "baz", Visibility::Protected)
+    ///     .set_final()
+    ///     .type_hint(BuiltinType::String);
+    /// ```
+    pub fn add_constant(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<Scalar>,
+        visibility: Visibility,
+    ) -> &mut ConstantEntity {
+        self.constant_entities
+            .push(ConstantEntity::new(name, value, visibility));
+        self.constant_entities.last_mut().unwrap()
     }
 
     /// Register class to `extends` the parent class.
@@ -214,6 +486,35 @@ impl ClassEntity {
         self.interfaces.push(Box::new(interface));
     }
 
+    /// Declare the class as `abstract`, which can't be instantiated.
+    #[inline]
+    pub fn set_abstract(&mut self) -> &mut Self {
+        self.class.ce_flags |= ZEND_ACC_EXPLICIT_ABSTRACT_CLASS;
+        self
+    }
+
+    /// Declare the class as `final`, which can't be extended.
+    ///
+    /// The class can't be both `abstract` and `final`, otherwise the module
+    /// startup fails.
+    #[inline]
+    pub fn set_final(&mut self) -> &mut Self {
+        self.class.ce_flags |= ZEND_ACC_FINAL;
+        self
+    }
+
+    /// Declare the class as `readonly`, all of its properties become
+    /// readonly, and the dynamic properties are forbidden.
+    ///
+    /// Readonly classes are supported since PHP 8.2, and the properties
+    /// should be typed, non-static and without default value, see
+    /// [PropertyEntity::set_readonly], otherwise the module startup fails.
+    #[inline]
+    pub fn set_readonly(&mut self) -> &mut Self {
+        self.readonly = true;
+        self
+    }
+
     /// Bind to static [StaticStateClass].
     ///
     /// When the class registered, the [StaticStateClass] will be initialized,
@@ -222,6 +523,556 @@ impl ClassEntity {
         self.bind_class = Some(cls);
     }
 
+    /// Hook the property reading, like `$obj->foo`, so the property can be
+    /// mapped onto the state.
+    ///
+    /// Returns `None` to fall back to the standard handler, which reads the
+    /// declared or dynamic property. The hooked properties are also checked by
+    /// `isset()` and `empty()` with the returned value, unless
+    /// [ClassEntity::has_property] handles them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{classes::ClassEntity, values::ZVal};
+    ///
+    /// #[derive(Default)]
+    /// struct Point {
+    ///     x: i64,
+    /// }
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<Point>("Point");
+    /// class.read_property(|this, name| {
+    ///     let point = this.try_as_state::<Point>()?;
+    ///     Ok::<_, phper::Error>(match name.to_str()? {
+    ///         "x" => Some(ZVal::from(point.x)),
+    ///         _ => None,
+    ///     })
+    /// });
+    /// class.write_property(|this, name, value| {
+    ///     let point = this.try_as_mut_state::<Point>()?;
+    ///     Ok::<_, phper::Error>(match name.to_str()? {
+    ///         "x" => {
+    ///             point.x = value.expect_long()?;
+    ///             true
+    ///         }
+    ///         _ => false,
+    ///     })
+    /// });
+    /// ```
+    pub fn read_property<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, &ZStr) -> Result<Option<ZVal>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.read_property = Some(Box::new(move |this: &mut StateObj, name: &ZStr| {
+            hook(this, name).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                Some(ZVal::default())
+            })
+        }));
+    }
+
+    /// Hook the property writing, like `$obj->foo = 1`, see
+    /// [ClassEntity::read_property].
+    ///
+    /// Returns `false` to fall back to the standard handler.
+    pub fn write_property<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, &ZStr, &mut ZVal) -> Result<bool, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.write_property = Some(Box::new(
+            move |this: &mut StateObj, name: &ZStr, value: &mut ZVal| {
+                hook(this, name, value).unwrap_or_else(|e| {
+                    unsafe { throw(e) };
+                    true
+                })
+            },
+        ));
+    }
+
+    /// Hook the property checking, like `isset($obj->foo)`, `empty($obj->foo)`
+    /// and `property_exists($obj, 'foo')`, see [PropertyCheck].
+    ///
+    /// Returns `None` to fall back to the standard handler.
+    pub fn has_property<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, &ZStr, PropertyCheck) -> Result<Option<bool>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.has_property = Some(Box::new(move |this: &mut StateObj, name: &ZStr, check| {
+            hook(this, name, check).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                Some(false)
+            })
+        }));
+    }
+
+    /// Hook the property unsetting, like `unset($obj->foo)`.
+    ///
+    /// Returns `false` to fall back to the standard handler.
+    pub fn unset_property<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, &ZStr) -> Result<bool, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.unset_property = Some(Box::new(move |this: &mut StateObj, name: &ZStr| {
+            hook(this, name).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                true
+            })
+        }));
+    }
+
+    /// Hook the fetching of property pointer, which is used by the indirect
+    /// modification, like `$obj->foo[] = 1` or `$obj->foo++`, so the `ZVal`
+    /// held by state can be modified in place.
+    ///
+    /// Returns `None` to fall back, the properties handled by
+    /// [ClassEntity::read_property] are modified through the read and write
+    /// hooks, others through the standard handler.
+    pub fn get_property_ptr_ptr<F>(&mut self, hook: F)
+    where
+        F: for<'a> Fn(&'a mut StateObj, &ZStr) -> Option<&'a mut ZVal> + 'static,
+    {
+        self.hooks.get_property_ptr_ptr = Some(Box::new(hook));
+    }
+
+    /// Hook the debug info of object, used by `var_dump` and `print_r`, so the
+    /// state can be shown instead of the properties.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{arrays::ZArray, classes::ClassEntity};
+    ///
+    /// #[derive(Default)]
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<Point>("Point");
+    /// class.get_debug_info(|this| {
+    ///     let point = this.try_as_state::<Point>()?;
+    ///     let mut arr = ZArray::new();
+    ///     arr.insert("x", point.x);
+    ///     arr.insert("y", point.y);
+    ///     Ok::<_, phper::Error>(arr)
+    /// });
+    /// ```
+    pub fn get_debug_info<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj) -> Result<ZArray, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.get_debug_info = Some(Box::new(move |this: &mut StateObj| {
+            hook(this).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                ZArray::new()
+            })
+        }));
+    }
+
+    /// Hook the properties fetched for the [PropertyPurpose], like the `(array)`
+    /// cast, `var_export` and `json_encode`.
+    ///
+    /// Returns `None` to fall back to the standard handler, which uses
+    /// [ClassEntity::get_debug_info] for [PropertyPurpose::Debug].
+    pub fn get_properties_for<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, PropertyPurpose) -> Result<Option<ZArray>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.get_properties_for = Some(Box::new(
+            move |this: &mut StateObj, purpose: PropertyPurpose| {
+                hook(this, purpose).unwrap_or_else(|e| {
+                    unsafe { throw(e) };
+                    Some(ZArray::new())
+                })
+            },
+        ));
+    }
+
+    /// Hook the arithmetic, bitwise and concatenation operators, like `$a + $b`,
+    /// see [Operator].
+    ///
+    /// Either operand may be the object of this class, like `2 * $a`, and for
+    /// the compound assignment, like `$a += $b`, the result replaces `$a`.
+    ///
+    /// Returns `None` to fall back to the standard behavior, which throws the
+    /// unsupported operand types error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{
+    ///     classes::{ClassEntity, StaticStateClass, handlers::Operator},
+    ///     values::ZVal,
+    /// };
+    ///
+    /// static MONEY_CLASS: StaticStateClass = StaticStateClass::null();
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<i64>("Money");
+    /// class.bind(&MONEY_CLASS);
+    /// class.do_operation(|operator, op1, op2| {
+    ///     let amount = |op: &ZVal| match op.as_z_obj() {
+    ///         Some(obj) if obj.get_class().is_instance_of(MONEY_CLASS.as_class_entry()) => {
+    ///             unsafe { obj.as_state_obj().try_as_state::<i64>().copied() }
+    ///         }
+    ///         _ => Ok(op.as_long().unwrap_or_default()),
+    ///     };
+    ///     let amount = match operator {
+    ///         Operator::Add => amount(op1)? + amount(op2)?,
+    ///         Operator::Sub => amount(op1)? - amount(op2)?,
+    ///         _ => return Ok(None),
+    ///     };
+    ///     let mut money = MONEY_CLASS.init_object()?;
+    ///     *money.as_mut_state::<i64>() = amount;
+    ///     Ok::<_, phper::Error>(Some(money.into_z_object().into()))
+    /// });
+    /// ```
+    pub fn do_operation<F, E>(&mut self, hook: F)
+    where
+        F: Fn(Operator, &ZVal, &ZVal) -> Result<Option<ZVal>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.do_operation = Some(Box::new(
+            move |operator: Operator, op1: &ZVal, op2: &ZVal| {
+                hook(operator, op1, op2).unwrap_or_else(|e| {
+                    unsafe { throw(e) };
+                    Some(ZVal::default())
+                })
+            },
+        ));
+    }
+
+    /// Hook the comparison, like `$a == $b`, `$a < $b` and `$a <=> $b`, either
+    /// operand may be the object of this class.
+    ///
+    /// Returns `None` to fall back to the standard comparison.
+    pub fn compare<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&ZVal, &ZVal) -> Result<Option<Ordering>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.compare = Some(Box::new(move |op1: &ZVal, op2: &ZVal| {
+            hook(op1, op2).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                // Treated as uncomparable.
+                Some(Ordering::Greater)
+            })
+        }));
+    }
+
+    /// Hook the casting of object, like `(string) $obj`, `(int) $obj`,
+    /// `(float) $obj` and `(bool) $obj`, the [TypeInfo] is the target type,
+    /// like [TypeInfo::STRING], [TypeInfo::LONG], [TypeInfo::DOUBLE] and
+    /// [TypeInfo::BOOL].
+    ///
+    /// Returns `None` to fall back to the standard handler, which calls
+    /// `__toString` for string, and converts to `true` for bool. The returned
+    /// value should be the target type, otherwise the casting fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{classes::ClassEntity, types::TypeInfo, values::ZVal};
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<i64>("Decimal");
+    /// class.cast_object(|this, type_info| {
+    ///     let value = *this.try_as_state::<i64>()?;
+    ///     Ok::<_, phper::Error>(match type_info {
+    ///         TypeInfo::LONG => Some(ZVal::from(value)),
+    ///         TypeInfo::DOUBLE => Some(ZVal::from(value as f64)),
+    ///         TypeInfo::BOOL => Some(ZVal::from(value != 0)),
+    ///         _ => None,
+    ///     })
+    /// });
+    /// ```
+    pub fn cast_object<F, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, TypeInfo) -> Result<Option<ZVal>, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.cast_object = Some(Box::new(move |this: &mut StateObj, type_info: TypeInfo| {
+            hook(this, type_info).unwrap_or_else(|e| {
+                unsafe { throw(e) };
+                // Mismatches the target type, so the casting fails.
+                Some(ZVal::default())
+            })
+        }));
+    }
+
+    /// Add the `__toString` method by the hook, and implement the
+    /// `Stringable` interface, so the object can be used in string contexts,
+    /// like `(string) $obj` and `"$obj"`.
+    pub fn to_string<F, S, E>(&mut self, hook: F) -> &mut MethodEntity
+    where
+        F: Fn(&mut StateObj) -> Result<S, E> + 'static,
+        S: AsRef<str>,
+        E: Throwable + 'static,
+    {
+        self.stringable = true;
+        self.add_method("__toString", Visibility::Public, move |this, _| {
+            hook(this).map(|s| s.as_ref().to_owned())
+        })
+        .return_type(BuiltinType::String)
+    }
+
+    /// Implement the `Countable` interface by the state `T`, which should be
+    /// the state type of class, so `count($obj)` calls [Countable::count]
+    /// directly, without calling the `count` method.
+    ///
+    /// If the state of object isn't `T`, the `Error` is thrown.
+    pub fn implements_countable<T: Countable + 'static>(&mut self) {
+        self.hooks.countable = Some(countable_state::<T>);
+        self.add_method("count", Visibility::Public, |this, _| {
+            this.try_as_mut_state::<T>()?.count()
+        })
+        .return_type(BuiltinType::Int);
+        self.implements(countable_interface);
+    }
+
+    /// Implement the `ArrayAccess` interface by the state `T`, which should be
+    /// the state type of class, so `$obj[$offset]` calls the [ArrayAccess]
+    /// methods directly, without calling the `offsetGet` like methods.
+    ///
+    /// If the state of object isn't `T`, the `Error` is thrown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{classes::{ClassEntity, handlers::ArrayAccess}, values::ZVal};
+    ///
+    /// #[derive(Default)]
+    /// struct Bag(Vec<ZVal>);
+    ///
+    /// impl ArrayAccess for Bag {
+    ///     fn offset_exists(&mut self, offset: &ZVal) -> phper::Result<bool> {
+    ///         Ok((offset.expect_long()? as usize) < self.0.len())
+    ///     }
+    ///
+    ///     fn offset_get(&mut self, offset: &ZVal) -> phper::Result<ZVal> {
+    ///         let index = offset.expect_long()? as usize;
+    ///         Ok(self.0.get(index).cloned().unwrap_or_default())
+    ///     }
+    ///
+    ///     fn offset_set(&mut self, offset: Option<&ZVal>, value: ZVal) -> phper::Result<()> {
+    ///         match offset {
+    ///             Some(offset) => self.0[offset.expect_long()? as usize] = value,
+    ///             None => self.0.push(value),
+    ///         }
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn offset_unset(&mut self, offset: &ZVal) -> phper::Result<()> {
+    ///         self.0.remove(offset.expect_long()? as usize);
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<Bag>("Bag");
+    /// class.implements_array_access::<Bag>();
+    /// ```
+    pub fn implements_array_access<T: ArrayAccess + 'static>(&mut self) {
+        self.hooks.array_access = Some(array_access_state::<T>);
+        self.add_method("offsetExists", Visibility::Public, |this, arguments| {
+            this.try_as_mut_state::<T>()?.offset_exists(&arguments[0])
+        })
+        .argument(Argument::by_val("offset"))
+        .return_type(BuiltinType::Bool);
+        self.add_method("offsetGet", Visibility::Public, |this, arguments| {
+            this.try_as_mut_state::<T>()?.offset_get(&arguments[0])
+        })
+        .argument(Argument::by_val("offset"))
+        .return_type(BuiltinType::Mixed);
+        self.add_method("offsetSet", Visibility::Public, |this, arguments| {
+            let offset = (!arguments[0].get_type_info().is_null()).then(|| &arguments[0]);
+            let value = arguments[1].clone();
+            this.try_as_mut_state::<T>()?.offset_set(offset, value)
+        })
+        .arguments([Argument::by_val("offset"), Argument::by_val("value")])
+        .return_type(BuiltinType::Void);
+        self.add_method("offsetUnset", Visibility::Public, |this, arguments| {
+            this.try_as_mut_state::<T>()?.offset_unset(&arguments[0])
+        })
+        .argument(Argument::by_val("offset"))
+        .return_type(BuiltinType::Void);
+        self.implements(array_access_interface);
+    }
+
+    /// Make the object iterable by `foreach`, with the iterator created by the
+    /// hook, which yields the keys and values, and the class implements the
+    /// `Traversable` interface.
+    ///
+    /// The hook is called when the iterating starts, the iterator should be
+    /// `'static`, so it can't borrow the state, share the data by `Rc` or
+    /// clone it instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::classes::ClassEntity;
+    ///
+    /// #[derive(Default)]
+    /// struct Rows(Vec<String>);
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<Rows>("Rows");
+    /// class.get_iterator(|this| {
+    ///     let rows = this.try_as_state::<Rows>()?.0.clone();
+    ///     Ok::<_, phper::Error>(rows.into_iter().enumerate().map(|(i, row)| (i as i64, row)))
+    /// });
+    /// ```
+    pub fn get_iterator<F, I, K, V, E>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj) -> Result<I, E> + 'static,
+        I: Iterator<Item = (K, V)> + 'static,
+        K: Into<ZVal> + 'static,
+        V: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.hooks.get_iterator = Some(Box::new(move |this: &mut StateObj| match hook(this) {
+            Ok(iter) => Some(Box::new(iter.map(|(k, v)| (k.into(), v.into())))
+                as Box<dyn Iterator<Item = (ZVal, ZVal)>>),
+            Err(e) => {
+                unsafe { throw(e) };
+                None
+            }
+        }));
+    }
+
+    /// Report the values held by state to the cycle collector, like the
+    /// stored callbacks or child objects, so `gc_collect_cycles()` can reclaim
+    /// the cycles through the state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{classes::ClassEntity, values::ZVal};
+    ///
+    /// #[derive(Default)]
+    /// struct Holder {
+    ///     values: Vec<ZVal>,
+    /// }
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<Holder>("Holder");
+    /// class.get_gc(|this, gc| {
+    ///     for value in &this.as_state::<Holder>().values {
+    ///         gc.add_zval(value);
+    ///     }
+    /// });
+    /// ```
+    pub fn get_gc<F>(&mut self, hook: F)
+    where
+        F: Fn(&mut StateObj, &mut GcCollector) + 'static,
+    {
+        self.hooks.get_gc = Some(Box::new(hook));
+    }
+
+    /// Make the state survive `serialize` and `unserialize`, by adding the
+    /// methods `__serialize` and `__unserialize`.
+    ///
+    /// The `serialize` exports the state to array, and the `unserialize`
+    /// restores the state from the array, on the object created by the state
+    /// constructor. For the class whose state is built by the constructor, use
+    /// [`serializable_with_constructor`](Self::serializable_with_constructor)
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use phper::{arrays::ZArray, classes::ClassEntity};
+    ///
+    /// let mut class = ClassEntity::new_with_default_state_constructor::<i64>("Money");
+    /// class.serializable(
+    ///     |this| {
+    ///         let mut arr = ZArray::new();
+    ///         arr.insert("cents", *this.try_as_state::<i64>()?);
+    ///         Ok(arr)
+    ///     },
+    ///     |this, data| {
+    ///         let cents = data.get("cents").map(|cents| cents.expect_long());
+    ///         *this.try_as_mut_state::<i64>()? = cents.transpose()?.unwrap_or_default();
+    ///         Ok::<_, phper::Error>(())
+    ///     },
+    /// );
+    /// ```
+    pub fn serializable<S, U, E>(&mut self, serialize: S, unserialize: U)
+    where
+        S: Fn(&mut StateObj) -> Result<ZArray, E> + 'static,
+        U: Fn(&mut StateObj, &ZArr) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__serialize", Visibility::Public, move |this, _| {
+            serialize(this)
+        })
+        .return_type(BuiltinType::Array);
+        self.add_method(
+            "__unserialize",
+            Visibility::Public,
+            move |this, arguments| {
+                let data = arguments[0].expect_z_arr()?;
+                unserialize(this, data).map_err(crate::Error::throw)
+            },
+        )
+        .argument(Argument::by_val("data").with_type(BuiltinType::Array))
+        .return_type(BuiltinType::Void);
+    }
+
+    /// Make the state survive `serialize` and `unserialize` like
+    /// [`serializable`](Self::serializable), but the `unserialize` builds the
+    /// state from the array, so it works with
+    /// [`new_with_constructor`](Self::new_with_constructor), whose object is
+    /// created by `unserialize` without calling the constructor.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{arrays::ZArray, classes::ClassEntity, functions::Argument};
+    ///
+    /// struct Money {
+    ///     cents: i64,
+    /// }
+    ///
+    /// let mut class = ClassEntity::new_with_constructor(
+    ///     "Money",
+    ///     [Argument::by_val("cents")],
+    ///     |arguments| {
+    ///         Ok::<_, phper::Error>(Money {
+    ///             cents: arguments[0].expect_long()?,
+    ///         })
+    ///     },
+    /// );
+    /// class.serializable_with_constructor(
+    ///     |this| {
+    ///         let mut arr = ZArray::new();
+    ///         arr.insert("cents", this.try_as_state::<Money>()?.cents);
+    ///         Ok(arr)
+    ///     },
+    ///     |data| {
+    ///         let cents = data.get("cents").map(|cents| cents.expect_long());
+    ///         Ok::<_, phper::Error>(Money {
+    ///             cents: cents.transpose()?.unwrap_or_default(),
+    ///         })
+    ///     },
+    /// );
+    /// ```
+    pub fn serializable_with_constructor<S, U, T, E>(&mut self, serialize: S, unserialize: U)
+    where
+        S: Fn(&mut StateObj) -> Result<ZArray, E> + 'static,
+        U: Fn(&ZArr) -> Result<T, E> + 'static,
+        T: 'static,
+        E: Throwable + 'static,
+    {
+        self.serializable(serialize, move |this, data| {
+            let state = unserialize(data)?;
+            unsafe { ClassHandlers::initialize(this, Box::new(state)) };
+            Ok(())
+        });
+    }
+
     /// Add the state clone function, called when cloning PHP object.
     ///
     /// By default, the object registered by `phper` is uncloneable, if you
@@ -272,37 +1123,38 @@ impl ClassEntity {
 
         methods.push(zeroed::<zend_function_entry>());
 
-        // Store the state constructor pointer to zend_class_entry.
-        methods.push(self.take_state_constructor_into_function_entry());
-
-        // Store the state cloner pointer to zend_class_entry.
-        methods.push(self.take_state_cloner_into_function_entry());
-
         Box::into_raw(methods.into_boxed_slice()).cast()
     }
-
-    unsafe fn take_state_constructor_into_function_entry(&self) -> zend_function_entry {
-        let mut entry = zeroed::<zend_function_entry>();
-        let ptr = &mut entry as *mut _ as *mut *const StateConstructor;
-        let state_constructor = Rc::into_raw(self.state_constructor.clone());
-        ptr.write(state_constructor);
-        entry
-    }
-
-    unsafe fn take_state_cloner_into_function_entry(&self) -> zend_function_entry {
-        let mut entry = zeroed::<zend_function_entry>();
-        let ptr = &mut entry as *mut _ as *mut *const StateCloner;
-        if let Some(state_cloner) = &self.state_cloner {
-            let state_constructor = Rc::into_raw(state_cloner.clone());
-            ptr.write(state_constructor);
-        }
-        entry
-    }
 }
 
 impl crate::modules::Registerer for ClassEntity {
     fn register(&mut self, _: i32) -> Result<(), Box<dyn std::error::Error>> {
         unsafe {
+            let class_name =
+                String::from_utf8_lossy(ZStr::from_ptr(self.class.name).to_bytes()).into_owned();
+            let has_iterator = self.hooks.get_iterator.is_some();
+
+            if self.class.ce_flags & ZEND_ACC_EXPLICIT_ABSTRACT_CLASS != 0
+                && self.class.ce_flags & ZEND_ACC_FINAL != 0
+            {
+                return Err(
+                    format!("Class {} can't be both abstract and final", class_name).into(),
+                );
+            }
+
+            if self.readonly {
+                if !phper_class_entry_set_readonly(&mut self.class) {
+                    return Err(format!(
+                        "Readonly class {} is supported since PHP 8.2",
+                        class_name
+                    )
+                    .into());
+                }
+                for property in &mut self.property_entities {
+                    property.set_readonly();
+                }
+            }
+
             let parent: *mut zend_class_entry = self
                 .parent
                 .as_ref()
@@ -313,19 +1165,51 @@ impl crate::modules::Registerer for ClassEntity {
             let class_ce =
                 phper_register_class_entry(&mut self.class, parent, self.function_entries());
 
+            // The objects, including the objects of userland subclasses, find
+            // the state constructor and handlers by the registry.
+            ClassHandlers::register(
+                class_ce,
+                self.state_constructor.clone(),
+                self.state_cloner.clone(),
+                take(&mut self.hooks),
+                self.built_by_constructor,
+            );
+
             if let Some(bind_class) = self.bind_class {
                 bind_class.bind(class_ce);
             }
 
+            // Should be set before implementing `Traversable`, otherwise the
+            // engine refuses it.
+            if has_iterator {
+                (*class_ce).get_iterator = Some(get_iterator);
+            }
+
             for interface in &self.interfaces {
                 let interface_ce = interface().as_ptr();
                 zend_class_implements(class_ce, 1, interface_ce);
             }
 
+            let stringable = stringable_interface();
+            if self.stringable && !ClassEntry::from_ptr(class_ce).is_instance_of(stringable) {
+                zend_class_implements(class_ce, 1, stringable.as_ptr());
+            }
+
+            let traversable = traversable_interface();
+            if has_iterator && !ClassEntry::from_ptr(class_ce).is_instance_of(traversable) {
+                zend_class_implements(class_ce, 1, traversable.as_ptr());
+            }
+
             (*class_ce).__bindgen_anon_2.create_object = Some(create_object);
 
+            // The constants are declared first, which may be referred by the
+            // default values of properties.
+            for constant in &self.constant_entities {
+                constant.declare(class_ce)?;
+            }
+
             for property in &self.property_entities {
-                property.declare(class_ce);
+                property.declare(class_ce)?;
             }
         }
 
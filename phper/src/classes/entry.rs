@@ -2,12 +2,13 @@
 
 use crate::{
     arrays::ZArr,
-    errors::{ClassNotFoundError, InitializeObjectError},
-    objects::ZObject,
+    errors::{ClassNotFoundError, EnumCaseNotFoundError, InitializeObjectError},
+    objects::{ZObj, ZObject},
     strings::ZStr,
     sys::*,
     values::ZVal,
 };
+use phper_alloc::ToRefOwned;
 use std::{
     borrow::ToOwned,
     fmt::Debug,
@@ -133,6 +134,38 @@ impl ClassEntry {
         }
     }
 
+    /// Get the case object of enum by name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::classes::ClassEntry;
+    ///
+    /// fn example() -> phper::Result<()> {
+    ///     let hearts = ClassEntry::from_globals("Suit")?.get_enum_case("Hearts")?;
+    ///     assert_eq!(hearts.get_enum_case_name().unwrap().to_str()?, "Hearts");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_enum_case(&self, case_name: impl AsRef<str>) -> crate::Result<ZObject> {
+        let case_name = case_name.as_ref();
+        unsafe {
+            let ptr = phper_enum_get_case(
+                self.as_ptr() as *mut _,
+                case_name.as_ptr().cast(),
+                case_name.len(),
+            );
+            match ZObj::try_from_mut_ptr(ptr) {
+                Some(case) => Ok(case.to_ref_owned()),
+                None => Err(EnumCaseNotFoundError::new(
+                    self.get_name().to_str()?.to_owned(),
+                    case_name.to_owned(),
+                )
+                .into()),
+            }
+        }
+    }
+
     /// Get the class name.
     pub fn get_name(&self) -> &ZStr {
         unsafe { ZStr::from_ptr(self.inner.name) }
@@ -0,0 +1,953 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to [zend_object_handlers], which are hooked by
+//! [ClassEntity](super::entity::ClassEntity).
+
+use crate::{
+    arrays::ZArray,
+    errors::{throw, NotInitializedError},
+    objects::{StateObj, UninitState, ZObj},
+    strings::ZStr,
+    sys::*,
+    types::TypeInfo,
+    values::ZVal,
+};
+use phper_alloc::ToRefOwned;
+use std::{
+    any::Any,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    ffi::c_void,
+    marker::PhantomData,
+    mem::{size_of, zeroed},
+    os::raw::c_int,
+    ptr::{addr_of_mut, drop_in_place, null, null_mut},
+    rc::Rc,
+    sync::RwLock,
+};
+
+use super::{clone_object, free_object, is_uninitialized, StateCloner, StateConstructor};
+
+/// The check type of [ClassEntity::has_property](super::entity::ClassEntity::has_property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyCheck {
+    /// Like `isset($obj->foo)`, the property exists and isn't null.
+    Isset,
+
+    /// Like `!empty($obj->foo)`, the property exists and is truthy.
+    NotEmpty,
+
+    /// Like `property_exists($obj, 'foo')`, the property exists.
+    Exists,
+}
+
+impl PropertyCheck {
+    fn from_raw(has_set_exists: c_int) -> Self {
+        match has_set_exists as u32 {
+            ZEND_PROPERTY_ISSET => Self::Isset,
+            ZEND_PROPERTY_EXISTS => Self::Exists,
+            _ => Self::NotEmpty,
+        }
+    }
+}
+
+/// The purpose of [ClassEntity::get_properties_for](super::entity::ClassEntity::get_properties_for).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PropertyPurpose {
+    /// Used by `var_dump` and `print_r`, unless
+    /// [ClassEntity::get_debug_info](super::entity::ClassEntity::get_debug_info)
+    /// is hooked.
+    Debug,
+
+    /// Used by the `(array)` cast.
+    ArrayCast,
+
+    /// Used by `serialize`.
+    Serialize,
+
+    /// Used by `var_export`.
+    VarExport,
+
+    /// Used by `json_encode`.
+    Json,
+}
+
+impl PropertyPurpose {
+    #[allow(non_upper_case_globals)]
+    fn from_raw(purpose: zend_prop_purpose) -> Option<Self> {
+        match purpose {
+            _zend_prop_purpose_ZEND_PROP_PURPOSE_DEBUG => Some(Self::Debug),
+            _zend_prop_purpose_ZEND_PROP_PURPOSE_ARRAY_CAST => Some(Self::ArrayCast),
+            _zend_prop_purpose_ZEND_PROP_PURPOSE_SERIALIZE => Some(Self::Serialize),
+            _zend_prop_purpose_ZEND_PROP_PURPOSE_VAR_EXPORT => Some(Self::VarExport),
+            _zend_prop_purpose_ZEND_PROP_PURPOSE_JSON => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// The operator of [ClassEntity::do_operation](super::entity::ClassEntity::do_operation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Operator {
+    /// `$a + $b`.
+    Add,
+
+    /// `$a - $b`.
+    Sub,
+
+    /// `$a * $b`, also used by the negation `-$a` as `$a * -1`.
+    Mul,
+
+    /// `$a / $b`.
+    Div,
+
+    /// `$a % $b`.
+    Mod,
+
+    /// `$a ** $b`.
+    Pow,
+
+    /// `$a . $b`.
+    Concat,
+
+    /// `$a << $b`.
+    ShiftLeft,
+
+    /// `$a >> $b`.
+    ShiftRight,
+
+    /// `$a & $b`.
+    BitwiseAnd,
+
+    /// `$a | $b`.
+    BitwiseOr,
+
+    /// `$a ^ $b`.
+    BitwiseXor,
+
+    /// `~$a`, the unary operator, so the second operand is null.
+    BitwiseNot,
+}
+
+impl Operator {
+    fn from_raw(opcode: u8) -> Option<Self> {
+        match opcode as u32 {
+            ZEND_ADD => Some(Self::Add),
+            ZEND_SUB => Some(Self::Sub),
+            ZEND_MUL => Some(Self::Mul),
+            ZEND_DIV => Some(Self::Div),
+            ZEND_MOD => Some(Self::Mod),
+            ZEND_POW => Some(Self::Pow),
+            ZEND_CONCAT => Some(Self::Concat),
+            ZEND_SL => Some(Self::ShiftLeft),
+            ZEND_SR => Some(Self::ShiftRight),
+            ZEND_BW_AND => Some(Self::BitwiseAnd),
+            ZEND_BW_OR => Some(Self::BitwiseOr),
+            ZEND_BW_XOR => Some(Self::BitwiseXor),
+            ZEND_BW_NOT => Some(Self::BitwiseNot),
+            _ => None,
+        }
+    }
+}
+
+/// The state implements the `Countable` interface natively, see
+/// [ClassEntity::implements_countable](super::entity::ClassEntity::implements_countable).
+pub trait Countable {
+    /// Like `count($obj)`.
+    fn count(&mut self) -> crate::Result<i64>;
+}
+
+/// The state implements the `ArrayAccess` interface natively, see
+/// [ClassEntity::implements_array_access](super::entity::ClassEntity::implements_array_access).
+pub trait ArrayAccess {
+    /// Like `isset($obj[$offset])`.
+    fn offset_exists(&mut self, offset: &ZVal) -> crate::Result<bool>;
+
+    /// Like `$obj[$offset]`, returns null for the missing offset.
+    fn offset_get(&mut self, offset: &ZVal) -> crate::Result<ZVal>;
+
+    /// Like `$obj[$offset] = $value`, the `offset` is `None` for appending,
+    /// like `$obj[] = $value`.
+    fn offset_set(&mut self, offset: Option<&ZVal>, value: ZVal) -> crate::Result<()>;
+
+    /// Like `unset($obj[$offset])`.
+    fn offset_unset(&mut self, offset: &ZVal) -> crate::Result<()>;
+}
+
+/// The collector of [ClassEntity::get_gc](super::entity::ClassEntity::get_gc),
+/// which the values held by state are reported to, so the cycle collector can
+/// find the cycles through the state.
+pub struct GcCollector {
+    buffer: *mut zend_get_gc_buffer,
+    _p: PhantomData<*mut ()>,
+}
+
+impl GcCollector {
+    /// Reports the value held by state.
+    pub fn add_zval(&mut self, val: &ZVal) {
+        unsafe { phper_zend_get_gc_buffer_add_zval(self.buffer, val.as_ptr() as *mut _) }
+    }
+
+    /// Reports the object held by state.
+    pub fn add_object(&mut self, obj: &ZObj) {
+        unsafe { phper_zend_get_gc_buffer_add_obj(self.buffer, obj.as_ptr() as *mut _) }
+    }
+}
+
+pub(crate) type ReadPropertyHook = dyn Fn(&mut StateObj, &ZStr) -> Option<ZVal>;
+
+pub(crate) type WritePropertyHook = dyn Fn(&mut StateObj, &ZStr, &mut ZVal) -> bool;
+
+pub(crate) type HasPropertyHook = dyn Fn(&mut StateObj, &ZStr, PropertyCheck) -> Option<bool>;
+
+pub(crate) type UnsetPropertyHook = dyn Fn(&mut StateObj, &ZStr) -> bool;
+
+pub(crate) type PropertyPtrHook = dyn for<'a> Fn(&'a mut StateObj, &ZStr) -> Option<&'a mut ZVal>;
+
+pub(crate) type DebugInfoHook = dyn Fn(&mut StateObj) -> ZArray;
+
+pub(crate) type PropertiesForHook = dyn Fn(&mut StateObj, PropertyPurpose) -> Option<ZArray>;
+
+pub(crate) type DoOperationHook = dyn Fn(Operator, &ZVal, &ZVal) -> Option<ZVal>;
+
+pub(crate) type CompareHook = dyn Fn(&ZVal, &ZVal) -> Option<Ordering>;
+
+pub(crate) type CastObjectHook = dyn Fn(&mut StateObj, TypeInfo) -> Option<ZVal>;
+
+pub(crate) type GetIteratorHook =
+    dyn Fn(&mut StateObj) -> Option<Box<dyn Iterator<Item = (ZVal, ZVal)>>>;
+
+pub(crate) type GetGcHook = dyn Fn(&mut StateObj, &mut GcCollector);
+
+pub(crate) type CountableState = fn(&mut StateObj) -> crate::Result<&mut dyn Countable>;
+
+pub(crate) type ArrayAccessState = fn(&mut StateObj) -> crate::Result<&mut dyn ArrayAccess>;
+
+pub(crate) fn countable_state<T: Countable + 'static>(
+    this: &mut StateObj,
+) -> crate::Result<&mut dyn Countable> {
+    Ok(this.try_as_mut_state::<T>()?)
+}
+
+pub(crate) fn array_access_state<T: ArrayAccess + 'static>(
+    this: &mut StateObj,
+) -> crate::Result<&mut dyn ArrayAccess> {
+    Ok(this.try_as_mut_state::<T>()?)
+}
+
+/// The hooks of object handlers, `None` results fall back to the standard
+/// handlers.
+#[derive(Default)]
+pub(crate) struct ObjectHooks {
+    pub(crate) read_property: Option<Box<ReadPropertyHook>>,
+    pub(crate) write_property: Option<Box<WritePropertyHook>>,
+    pub(crate) has_property: Option<Box<HasPropertyHook>>,
+    pub(crate) unset_property: Option<Box<UnsetPropertyHook>>,
+    pub(crate) get_property_ptr_ptr: Option<Box<PropertyPtrHook>>,
+    pub(crate) get_debug_info: Option<Box<DebugInfoHook>>,
+    pub(crate) get_properties_for: Option<Box<PropertiesForHook>>,
+    pub(crate) do_operation: Option<Box<DoOperationHook>>,
+    pub(crate) compare: Option<Box<CompareHook>>,
+    pub(crate) cast_object: Option<Box<CastObjectHook>>,
+    pub(crate) countable: Option<CountableState>,
+    pub(crate) array_access: Option<ArrayAccessState>,
+    pub(crate) get_iterator: Option<Box<GetIteratorHook>>,
+    pub(crate) get_gc: Option<Box<GetGcHook>>,
+}
+
+/// The registry of the classes registered by `phper`, maps the class entry to
+/// its [ClassHandlers], stored as addresses.
+static CLASS_HANDLERS: RwLock<BTreeMap<usize, usize>> = RwLock::new(BTreeMap::new());
+
+thread_local! {
+    /// The handlers found for the internal class entries, which live as long as
+    /// the process, so the registry is only looked up on the first creation.
+    static FOUND_CLASS_HANDLERS: RefCell<HashMap<usize, usize>> = Default::default();
+
+    /// The handlers of the objects whose class isn't registered, which keep
+    /// the object uninitialized.
+    static UNREGISTERED_CLASS_HANDLERS: &'static ClassHandlers = Box::leak(Box::new(
+        ClassHandlers::new(
+            Rc::new(|| Box::into_raw(Box::new(UninitState) as Box<dyn Any>)),
+            None,
+            Default::default(),
+        ),
+    ));
+}
+
+/// The object handlers shared by the objects of class, followed by the
+/// metadata of class, so they can be found by `zend_object.handlers`.
+#[repr(C)]
+pub(crate) struct ClassHandlers {
+    handlers: zend_object_handlers,
+    iterator_funcs: zend_object_iterator_funcs,
+    pub(crate) state_constructor: Rc<StateConstructor>,
+    pub(crate) state_cloner: Option<Rc<StateCloner>>,
+    hooks: ObjectHooks,
+    uninitialized: Option<Box<ClassHandlers>>,
+    initialized: *const ClassHandlers,
+}
+
+impl ClassHandlers {
+    /// Builds the handlers of class and registers them, which live as long as
+    /// the class.
+    ///
+    /// If the state is built by the constructor, the new objects use the
+    /// handlers without hooks and uncloneable, until the constructor called.
+    pub(crate) unsafe fn register(
+        ce: *const zend_class_entry,
+        state_constructor: Rc<StateConstructor>,
+        state_cloner: Option<Rc<StateCloner>>,
+        hooks: ObjectHooks,
+        built_by_constructor: bool,
+    ) {
+        let mut class_handlers = Self::new(state_constructor.clone(), state_cloner, hooks);
+        if built_by_constructor {
+            let uninitialized = Self::new(state_constructor, None, Default::default());
+            class_handlers.uninitialized = Some(Box::new(uninitialized));
+        }
+        let class_handlers = Box::into_raw(Box::new(class_handlers));
+        if let Some(uninitialized) = &mut (*class_handlers).uninitialized {
+            uninitialized.initialized = class_handlers;
+        }
+        CLASS_HANDLERS
+            .write()
+            .unwrap()
+            .insert(ce as usize, class_handlers as usize);
+    }
+
+    /// Finds the handlers of class, for the subclass, they are the handlers of
+    /// the nearest ancestor registered by `phper`.
+    ///
+    /// The userland classes are freed at the request end, so only walked
+    /// through to their internal parent, whose handlers are cached.
+    pub(crate) unsafe fn find<'a>(mut ce: *const zend_class_entry) -> Option<&'a Self> {
+        while !ce.is_null() && (*ce).type_ as u32 == ZEND_USER_CLASS {
+            ce = (*ce).__bindgen_anon_1.parent;
+        }
+        if ce.is_null() {
+            return None;
+        }
+        let class_handlers = FOUND_CLASS_HANDLERS.with(|found| {
+            if let Some(&class_handlers) = found.borrow().get(&(ce as usize)) {
+                return Some(class_handlers);
+            }
+            let class_handlers = Self::find_registered(ce)?;
+            found.borrow_mut().insert(ce as usize, class_handlers);
+            Some(class_handlers)
+        })?;
+        Some(&*(class_handlers as *const Self))
+    }
+
+    unsafe fn find_registered(mut ce: *const zend_class_entry) -> Option<usize> {
+        let registry = CLASS_HANDLERS.read().unwrap();
+        while !ce.is_null() {
+            if let Some(&class_handlers) = registry.get(&(ce as usize)) {
+                return Some(class_handlers);
+            }
+            ce = (*ce).__bindgen_anon_1.parent;
+        }
+        None
+    }
+
+    /// The handlers of the objects whose class isn't registered by `phper`,
+    /// which can't be initialized.
+    pub(crate) fn unregistered<'a>() -> &'a Self {
+        UNREGISTERED_CLASS_HANDLERS.with(|class_handlers| *class_handlers)
+    }
+
+    fn new(
+        state_constructor: Rc<StateConstructor>,
+        state_cloner: Option<Rc<StateCloner>>,
+        hooks: ObjectHooks,
+    ) -> Self {
+        let mut handlers = unsafe { std_object_handlers };
+        handlers.offset = StateObj::offset() as c_int;
+        handlers.free_obj = Some(free_object);
+        handlers.clone_obj = state_cloner.is_some().then_some(clone_object);
+
+        if hooks.read_property.is_some() {
+            handlers.read_property = Some(read_property);
+        }
+        if hooks.write_property.is_some() {
+            handlers.write_property = Some(write_property);
+        }
+        if hooks.read_property.is_some() || hooks.has_property.is_some() {
+            handlers.has_property = Some(has_property);
+        }
+        if hooks.unset_property.is_some() {
+            handlers.unset_property = Some(unset_property);
+        }
+        if hooks.read_property.is_some() || hooks.get_property_ptr_ptr.is_some() {
+            handlers.get_property_ptr_ptr = Some(get_property_ptr_ptr);
+        }
+        if hooks.get_debug_info.is_some() {
+            handlers.get_debug_info = Some(get_debug_info);
+        }
+        if hooks.get_properties_for.is_some() {
+            handlers.get_properties_for = Some(get_properties_for);
+        }
+        if hooks.do_operation.is_some() {
+            handlers.do_operation = Some(do_operation);
+        }
+        if hooks.compare.is_some() {
+            handlers.compare = Some(compare);
+        }
+        if hooks.cast_object.is_some() {
+            handlers.cast_object = Some(cast_object);
+        }
+        if hooks.countable.is_some() {
+            handlers.count_elements = Some(count_elements);
+        }
+        if hooks.get_gc.is_some() {
+            handlers.get_gc = Some(get_gc);
+        }
+        if hooks.array_access.is_some() {
+            handlers.read_dimension = Some(read_dimension);
+            handlers.write_dimension = Some(write_dimension);
+            handlers.has_dimension = Some(has_dimension);
+            handlers.unset_dimension = Some(unset_dimension);
+        }
+
+        let mut iterator_funcs = unsafe { zeroed::<zend_object_iterator_funcs>() };
+        iterator_funcs.dtor = Some(iterator_dtor);
+        iterator_funcs.valid = Some(iterator_valid);
+        iterator_funcs.get_current_data = Some(iterator_get_current_data);
+        iterator_funcs.get_current_key = Some(iterator_get_current_key);
+        iterator_funcs.move_forward = Some(iterator_move_forward);
+        iterator_funcs.rewind = Some(iterator_rewind);
+
+        Self {
+            handlers,
+            iterator_funcs,
+            state_constructor,
+            state_cloner,
+            hooks,
+            uninitialized: None,
+            initialized: null(),
+        }
+    }
+
+    /// The handlers of the new object.
+    #[inline]
+    pub(crate) fn initial(&self) -> &Self {
+        self.uninitialized.as_deref().unwrap_or(self)
+    }
+
+    /// Stores the state built by the constructor, and switches the object to
+    /// the hooked handlers.
+    pub(crate) unsafe fn initialize(state_obj: &mut StateObj, state: Box<dyn Any>) {
+        state_obj.replace_state(state);
+        let object = state_obj.as_mut_object().as_mut_ptr();
+        if let Some(initialized) = Self::from_object(object).initialized.as_ref() {
+            (*object).handlers = initialized.as_handlers_ptr();
+        }
+    }
+
+    /// The raw handlers, which are set to `zend_object.handlers`.
+    #[inline]
+    pub(crate) fn as_handlers_ptr(&self) -> *const zend_object_handlers {
+        &self.handlers
+    }
+
+    pub(crate) unsafe fn from_object<'a>(object: *const zend_object) -> &'a Self {
+        &*((*object).handlers as *const Self)
+    }
+}
+
+unsafe extern "C" fn read_property(
+    object: *mut zend_object,
+    member: *mut zend_string,
+    type_: c_int,
+    cache_slot: *mut *mut c_void,
+    rv: *mut zval,
+) -> *mut zval {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    if let Some(read) = &hooks.read_property {
+        let state_obj = StateObj::from_mut_object_ptr(object);
+        if let Some(val) = read(state_obj, ZStr::from_ptr(member)) {
+            rv.cast::<ZVal>().write(val);
+            return rv;
+        }
+    }
+    zend_std_read_property(object, member, type_, cache_slot, rv)
+}
+
+unsafe extern "C" fn write_property(
+    object: *mut zend_object,
+    member: *mut zend_string,
+    value: *mut zval,
+    cache_slot: *mut *mut c_void,
+) -> *mut zval {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    if let Some(write) = &hooks.write_property {
+        let state_obj = StateObj::from_mut_object_ptr(object);
+        if write(state_obj, ZStr::from_ptr(member), ZVal::from_mut_ptr(value)) {
+            return value;
+        }
+    }
+    zend_std_write_property(object, member, value, cache_slot)
+}
+
+unsafe extern "C" fn has_property(
+    object: *mut zend_object,
+    member: *mut zend_string,
+    has_set_exists: c_int,
+    cache_slot: *mut *mut c_void,
+) -> c_int {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    let check = PropertyCheck::from_raw(has_set_exists);
+    let name = ZStr::from_ptr(member);
+
+    if let Some(has) = &hooks.has_property {
+        if let Some(b) = has(StateObj::from_mut_object_ptr(object), name, check) {
+            return b as c_int;
+        }
+    }
+
+    // The property handled by read hook is checked by its value.
+    if let Some(read) = &hooks.read_property {
+        if let Some(mut val) = read(StateObj::from_mut_object_ptr(object), name) {
+            return match check {
+                PropertyCheck::Isset => !val.get_type_info().is_null(),
+                PropertyCheck::NotEmpty => zend_is_true(val.as_mut_ptr()) as c_int != 0,
+                PropertyCheck::Exists => true,
+            } as c_int;
+        }
+    }
+
+    zend_std_has_property(object, member, has_set_exists, cache_slot)
+}
+
+unsafe extern "C" fn unset_property(
+    object: *mut zend_object,
+    member: *mut zend_string,
+    cache_slot: *mut *mut c_void,
+) {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    if let Some(unset) = &hooks.unset_property {
+        if unset(
+            StateObj::from_mut_object_ptr(object),
+            ZStr::from_ptr(member),
+        ) {
+            return;
+        }
+    }
+    zend_std_unset_property(object, member, cache_slot)
+}
+
+unsafe extern "C" fn get_property_ptr_ptr(
+    object: *mut zend_object,
+    member: *mut zend_string,
+    type_: c_int,
+    cache_slot: *mut *mut c_void,
+) -> *mut zval {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    let name = ZStr::from_ptr(member);
+
+    if let Some(ptr_ptr) = &hooks.get_property_ptr_ptr {
+        if let Some(val) = ptr_ptr(StateObj::from_mut_object_ptr(object), name) {
+            return val.as_mut_ptr();
+        }
+    }
+
+    // Returns null for the property handled by read hook, so the engine
+    // modifies it through the read and write hooks.
+    if let Some(read) = &hooks.read_property {
+        if read(StateObj::from_mut_object_ptr(object), name).is_some() {
+            return null_mut();
+        }
+    }
+
+    zend_std_get_property_ptr_ptr(object, member, type_, cache_slot)
+}
+
+unsafe extern "C" fn get_debug_info(
+    object: *mut zend_object,
+    is_temp: *mut c_int,
+) -> *mut HashTable {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    match &hooks.get_debug_info {
+        Some(get_debug_info) => {
+            *is_temp = 1;
+            get_debug_info(StateObj::from_mut_object_ptr(object)).into_raw()
+        }
+        None => zend_std_get_debug_info(object, is_temp),
+    }
+}
+
+unsafe extern "C" fn get_properties_for(
+    object: *mut zend_object,
+    purpose: zend_prop_purpose,
+) -> *mut zend_array {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    if let (Some(get_properties_for), Some(purpose)) = (
+        &hooks.get_properties_for,
+        PropertyPurpose::from_raw(purpose),
+    ) {
+        if let Some(arr) = get_properties_for(StateObj::from_mut_object_ptr(object), purpose) {
+            return arr.into_raw();
+        }
+    }
+    zend_std_get_properties_for(object, purpose)
+}
+
+/// Finds the operand object whose handler is hooked, `op1` first, as the engine
+/// does, because either operand may be the object of class.
+unsafe fn find_operand_object(
+    op1: *mut zval,
+    op2: *mut zval,
+    is_hooked: impl Fn(&zend_object_handlers) -> bool,
+) -> Option<*mut zend_object> {
+    [op1, op2]
+        .into_iter()
+        .filter(|op| !op.is_null())
+        .filter_map(|op| ZVal::from_mut_ptr(op).as_mut_z_obj())
+        .map(|obj| obj.as_mut_ptr())
+        .find(|&obj| is_hooked(&*(*obj).handlers))
+}
+
+unsafe extern "C" fn do_operation(
+    opcode: u8,
+    result: *mut zval,
+    op1: *mut zval,
+    op2: *mut zval,
+) -> zend_result {
+    let Some(operator) = Operator::from_raw(opcode) else {
+        return ZEND_RESULT_CODE_FAILURE;
+    };
+    let object = find_operand_object(op1, op2, |handlers| {
+        handlers.do_operation.map(|f| f as usize) == Some(do_operation as usize)
+    });
+    let Some(object) = object else {
+        return ZEND_RESULT_CODE_FAILURE;
+    };
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    let Some(do_operation) = &hooks.do_operation else {
+        return ZEND_RESULT_CODE_FAILURE;
+    };
+
+    let null = ZVal::default();
+    let rhs = if op2.is_null() {
+        &null
+    } else {
+        ZVal::from_ptr(op2)
+    };
+    match do_operation(operator, ZVal::from_ptr(op1), rhs) {
+        Some(val) => {
+            // The `result` is `op1` for the compound assignment, like `$a += $b`.
+            if result == op1 {
+                zval_ptr_dtor(op1);
+            }
+            result.cast::<ZVal>().write(val);
+            ZEND_RESULT_CODE_SUCCESS
+        }
+        None => ZEND_RESULT_CODE_FAILURE,
+    }
+}
+
+unsafe extern "C" fn compare(op1: *mut zval, op2: *mut zval) -> c_int {
+    let object = find_operand_object(op1, op2, |handlers| {
+        handlers.compare.map(|f| f as usize) == Some(compare as usize)
+    });
+    if let Some(object) = object {
+        let hooks = &ClassHandlers::from_object(object).hooks;
+        if let Some(compare) = &hooks.compare {
+            if let Some(ordering) = compare(ZVal::from_ptr(op1), ZVal::from_ptr(op2)) {
+                return ordering as c_int;
+            }
+        }
+    }
+    zend_std_compare_objects(op1, op2)
+}
+
+/// Checks the casted value is the target type, otherwise the engine reads it
+/// as the wrong type.
+fn is_cast_type(val: &ZVal, type_info: TypeInfo) -> bool {
+    let t = val.get_type_info();
+    match type_info.into_raw() {
+        _IS_BOOL => t.is_bool(),
+        _IS_NUMBER => t.is_long() || t.is_double(),
+        raw => t.get_base_type().into_raw() == raw,
+    }
+}
+
+unsafe extern "C" fn cast_object(
+    readobj: *mut zend_object,
+    retval: *mut zval,
+    type_: c_int,
+) -> zend_result {
+    let hooks = &ClassHandlers::from_object(readobj).hooks;
+    if let Some(cast_object) = &hooks.cast_object {
+        let type_info = TypeInfo::from_raw(type_ as u32);
+        if let Some(val) = cast_object(StateObj::from_mut_object_ptr(readobj), type_info) {
+            if !is_cast_type(&val, type_info) {
+                return ZEND_RESULT_CODE_FAILURE;
+            }
+            retval.cast::<ZVal>().write(val);
+            return ZEND_RESULT_CODE_SUCCESS;
+        }
+    }
+    zend_std_cast_object_tostring(readobj, retval, type_)
+}
+
+unsafe extern "C" fn count_elements(
+    object: *mut zend_object,
+    count: *mut zend_long,
+) -> zend_result {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    let Some(countable) = hooks.countable else {
+        return ZEND_RESULT_CODE_FAILURE;
+    };
+    match countable(StateObj::from_mut_object_ptr(object)).and_then(|state| state.count()) {
+        Ok(n) => {
+            *count = n;
+            ZEND_RESULT_CODE_SUCCESS
+        }
+        Err(e) => {
+            throw(e);
+            ZEND_RESULT_CODE_FAILURE
+        }
+    }
+}
+
+/// Gets the state implementing [ArrayAccess], throws if the state mismatches.
+unsafe fn object_array_access<'a>(object: *mut zend_object) -> Option<&'a mut dyn ArrayAccess> {
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    let array_access = hooks.array_access.unwrap();
+    match array_access(StateObj::from_mut_object_ptr(object)) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            throw(e);
+            None
+        }
+    }
+}
+
+unsafe extern "C" fn read_dimension(
+    object: *mut zend_object,
+    offset: *mut zval,
+    type_: c_int,
+    rv: *mut zval,
+) -> *mut zval {
+    let Some(state) = object_array_access(object) else {
+        return null_mut();
+    };
+    let null = ZVal::default();
+    let offset = if offset.is_null() {
+        &null
+    } else {
+        ZVal::from_ptr(offset)
+    };
+
+    // Like `isset($obj[$a][$b])`, checks the existence first.
+    if type_ as u32 == BP_VAR_IS {
+        match state.offset_exists(offset) {
+            Ok(true) => {}
+            Ok(false) => return null_mut(),
+            Err(e) => {
+                throw(e);
+                return null_mut();
+            }
+        }
+    }
+
+    match state.offset_get(offset) {
+        Ok(val) => {
+            rv.cast::<ZVal>().write(val);
+            rv
+        }
+        Err(e) => {
+            throw(e);
+            null_mut()
+        }
+    }
+}
+
+unsafe extern "C" fn write_dimension(
+    object: *mut zend_object,
+    offset: *mut zval,
+    value: *mut zval,
+) {
+    let Some(state) = object_array_access(object) else {
+        return;
+    };
+    let offset = (!offset.is_null()).then(|| ZVal::from_ptr(offset));
+    if let Err(e) = state.offset_set(offset, ZVal::from_ptr(value).clone()) {
+        throw(e);
+    }
+}
+
+unsafe extern "C" fn has_dimension(
+    object: *mut zend_object,
+    offset: *mut zval,
+    check_empty: c_int,
+) -> c_int {
+    let Some(state) = object_array_access(object) else {
+        return 0;
+    };
+    let offset = ZVal::from_ptr(offset);
+    let result = state.offset_exists(offset).and_then(|exists| {
+        // Like `empty($obj[$offset])`, checks the value further.
+        if exists && check_empty != 0 {
+            let mut val = state.offset_get(offset)?;
+            Ok(zend_is_true(val.as_mut_ptr()) as c_int != 0)
+        } else {
+            Ok(exists)
+        }
+    });
+    match result {
+        Ok(b) => b as c_int,
+        Err(e) => {
+            throw(e);
+            0
+        }
+    }
+}
+
+unsafe extern "C" fn unset_dimension(object: *mut zend_object, offset: *mut zval) {
+    let Some(state) = object_array_access(object) else {
+        return;
+    };
+    if let Err(e) = state.offset_unset(ZVal::from_ptr(offset)) {
+        throw(e);
+    }
+}
+
+/// The [zend_object_iterator] created by
+/// [ClassEntity::get_iterator](super::entity::ClassEntity::get_iterator), the
+/// `data` holds the iterated object.
+#[repr(C)]
+struct ObjectIterator {
+    iterator: zend_object_iterator,
+    inner: Option<Box<dyn Iterator<Item = (ZVal, ZVal)>>>,
+    current: Option<(ZVal, ZVal)>,
+}
+
+impl ObjectIterator {
+    unsafe fn from_iterator<'a>(iter: *mut zend_object_iterator) -> &'a mut Self {
+        &mut *iter.cast::<Self>()
+    }
+
+    unsafe fn object(&mut self) -> *mut zend_object {
+        ZVal::from_mut_ptr(&mut self.iterator.data)
+            .as_mut_z_obj()
+            .unwrap()
+            .as_mut_ptr()
+    }
+}
+
+pub(crate) unsafe extern "C" fn get_iterator(
+    _ce: *mut zend_class_entry,
+    object: *mut zval,
+    by_ref: c_int,
+) -> *mut zend_object_iterator {
+    if by_ref != 0 {
+        zend_throw_error(
+            null_mut(),
+            b"An iterator cannot be used with foreach by reference\0"
+                .as_ptr()
+                .cast(),
+        );
+        return null_mut();
+    }
+
+    let obj = ZVal::from_mut_ptr(object).as_mut_z_obj().unwrap();
+    if is_uninitialized(obj) {
+        let class_name = obj.get_class().get_name().to_str().unwrap_or_default();
+        throw(NotInitializedError::new(class_name.to_owned()));
+        return null_mut();
+    }
+    let class_handlers = ClassHandlers::from_object(obj.as_mut_ptr());
+
+    // Allocated by `emalloc`, because the iterator is freed by the objects
+    // store, after `dtor` called.
+    let iter = phper_emalloc(size_of::<ObjectIterator>()).cast::<ObjectIterator>();
+    zend_iterator_init(iter.cast());
+    addr_of_mut!((*iter).iterator.data)
+        .cast::<ZVal>()
+        .write(ZVal::from(obj.to_ref_owned()));
+    (*iter).iterator.funcs = &class_handlers.iterator_funcs;
+    // The inner iterator is created by `rewind`, which is called before
+    // iterating.
+    addr_of_mut!((*iter).inner).write(None);
+    addr_of_mut!((*iter).current).write(None);
+    iter.cast()
+}
+
+unsafe extern "C" fn iterator_dtor(iter: *mut zend_object_iterator) {
+    let iter = ObjectIterator::from_iterator(iter);
+    drop_in_place(&mut iter.inner);
+    drop_in_place(&mut iter.current);
+    zval_ptr_dtor(&mut iter.iterator.data);
+}
+
+unsafe extern "C" fn iterator_valid(iter: *mut zend_object_iterator) -> zend_result {
+    match ObjectIterator::from_iterator(iter).current {
+        Some(_) => ZEND_RESULT_CODE_SUCCESS,
+        None => ZEND_RESULT_CODE_FAILURE,
+    }
+}
+
+unsafe extern "C" fn iterator_get_current_data(iter: *mut zend_object_iterator) -> *mut zval {
+    match &mut ObjectIterator::from_iterator(iter).current {
+        Some((_, value)) => value.as_mut_ptr(),
+        None => null_mut(),
+    }
+}
+
+unsafe extern "C" fn iterator_get_current_key(iter: *mut zend_object_iterator, key: *mut zval) {
+    match &ObjectIterator::from_iterator(iter).current {
+        Some((k, _)) => key.cast::<ZVal>().write(k.clone()),
+        None => key.cast::<ZVal>().write(ZVal::default()),
+    }
+}
+
+unsafe extern "C" fn iterator_move_forward(iter: *mut zend_object_iterator) {
+    let iter = ObjectIterator::from_iterator(iter);
+    iter.current = iter.inner.as_mut().and_then(|inner| inner.next());
+}
+
+unsafe extern "C" fn iterator_rewind(iter: *mut zend_object_iterator) {
+    let iter = ObjectIterator::from_iterator(iter);
+    let object = iter.object();
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    iter.inner = hooks
+        .get_iterator
+        .as_ref()
+        .and_then(|get_iterator| get_iterator(StateObj::from_mut_object_ptr(object)));
+    iter.current = iter.inner.as_mut().and_then(|inner| inner.next());
+}
+
+unsafe extern "C" fn get_gc(
+    object: *mut zend_object,
+    table: *mut *mut zval,
+    n: *mut c_int,
+) -> *mut HashTable {
+    let buffer = zend_get_gc_buffer_create();
+    // Keeps the properties reported by the standard handler.
+    let properties = phper_zend_std_get_gc_into(object, buffer);
+
+    let hooks = &ClassHandlers::from_object(object).hooks;
+    if let Some(get_gc) = &hooks.get_gc {
+        let mut collector = GcCollector {
+            buffer,
+            _p: PhantomData,
+        };
+        get_gc(StateObj::from_mut_object_ptr(object), &mut collector);
+    }
+
+    phper_zend_get_gc_buffer_use(buffer, table, n);
+    properties
+}
@@ -13,26 +13,29 @@
 pub mod entity;
 /// Zend Class Entry
 pub mod entry;
+pub mod handlers;
 pub mod zend_classes;
 
 use crate::{
+    arrays::{InsertKey, ZArray},
+    errors::{throw, NotInitializedError},
     functions::{FunctionEntry, MethodEntity},
-    objects::{StateObj, StateObject, ZObject},
+    objects::{StateObj, StateObject, ZObj, ZObject},
+    strings::ZStr,
     sys::*,
-    types::Scalar,
+    types::{Scalar, TypeHint},
     values::ZVal,
 };
 use std::{
     any::Any,
     convert::TryInto,
+    error::Error,
     mem::{size_of, zeroed},
-    os::raw::c_int,
-    ptr::null_mut,
-    slice,
+    ptr::{null, null_mut},
     sync::atomic::{AtomicPtr, Ordering},
 };
 
-use self::entry::ClassEntry;
+use self::{entity::ClassEntity, entry::ClassEntry, handlers::ClassHandlers};
 
 #[allow(clippy::useless_conversion)]
 fn find_global_class_entry_ptr(name: impl AsRef<str>) -> *mut zend_class_entry {
@@ -169,6 +172,51 @@ impl StaticInterface {
     }
 }
 
+/// Class whose state is the Rust type, usually implemented by
+/// [`php_class`](crate::php_class).
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{classes::PhpClass, modules::Module, php_class, php_impl};
+///
+/// #[php_class(name = "Counter")]
+/// #[derive(Default)]
+/// pub struct Counter {
+///     count: i64,
+/// }
+///
+/// #[php_impl]
+/// impl Counter {
+///     pub fn increase(&mut self, step: Option<i64>) -> i64 {
+///         self.count += step.unwrap_or(1);
+///         self.count
+///     }
+/// }
+///
+/// let mut module = Module::new("test", "0.1.0", "");
+/// module.add_class(Counter::class_entity());
+/// ```
+pub trait PhpClass: PhpImpl + 'static {
+    /// The static class bound to the class entity, initialized after the class
+    /// is registered.
+    fn static_class() -> &'static StaticStateClass;
+
+    /// Build the class entity, with the methods registered by [`PhpImpl`].
+    fn class_entity() -> ClassEntity;
+}
+
+/// Methods of [`PhpClass`], usually implemented by
+/// [`php_impl`](crate::php_impl).
+pub trait PhpImpl {
+    /// Construct the class entity, whose state is built by the constructor if
+    /// declared, otherwise by `Default`.
+    fn new_class_entity(class_name: &str) -> ClassEntity;
+
+    /// Register the methods to class entity.
+    fn register_methods(class: &mut ClassEntity);
+}
+
 pub(crate) type StateConstructor = dyn Fn() -> *mut dyn Any;
 
 pub(crate) type StateCloner = dyn Fn(*const dyn Any) -> *mut dyn Any;
@@ -177,6 +225,7 @@ pub(crate) type StateCloner = dyn Fn(*const dyn Any) -> *mut dyn Any;
 pub struct InterfaceEntity {
     interface: zend_class_entry,
     method_entities: Vec<MethodEntity>,
+    constant_entities: Vec<ConstantEntity>,
     extends: Vec<Box<dyn Fn() -> &'static ClassEntry>>,
     bind_interface: Option<&'static StaticInterface>,
 }
@@ -192,6 +241,7 @@ impl InterfaceEntity {
                 phper_init_interface_entry(interface_name.as_ptr().cast(), interface_name_len)
             },
             method_entities: Vec::new(),
+            constant_entities: Vec::new(),
             extends: Vec::new(),
             bind_interface: None,
         }
@@ -206,6 +256,17 @@ impl InterfaceEntity {
         self.method_entities.last_mut().unwrap()
     }
 
+    /// Add constant to interface, with mandatory visibility public.
+    pub fn add_constant(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<Scalar>,
+    ) -> &mut ConstantEntity {
+        self.constant_entities
+            .push(ConstantEntity::new(name, value, Visibility::Public));
+        self.constant_entities.last_mut().unwrap()
+    }
+
     /// Register interface to `extends` the interfaces, due to the interface can
     /// extends multi interface, so this method can be called multi time.
     ///
@@ -261,25 +322,113 @@ impl crate::modules::Registerer for InterfaceEntity {
                 let interface_ce = interface().as_ptr();
                 zend_class_implements(class_ce, 1, interface_ce);
             }
+
+            for constant in &self.constant_entities {
+                constant.declare(class_ce)?;
+            }
         };
 
         Ok(())
     }
 }
 
+/// Builder for declaring class constant.
+pub struct ConstantEntity {
+    name: String,
+    value: Scalar,
+    flags: u32,
+    type_hint: Option<TypeHint>,
+}
+
+impl ConstantEntity {
+    pub(crate) fn new(
+        name: impl Into<String>,
+        value: impl Into<Scalar>,
+        visibility: Visibility,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            flags: visibility as u32,
+            type_hint: None,
+        }
+    }
+
+    /// Declare the constant as `final`, which can't be overridden by the
+    /// subclasses.
+    #[inline]
+    pub fn set_final(&mut self) -> &mut Self {
+        self.flags |= ZEND_ACC_FINAL;
+        self
+    }
+
+    /// Declare the type of constant, like `const int FOO = 1;`.
+    ///
+    /// Typed class constants are supported since PHP 8.3, declaring them in
+    /// the older versions makes the module startup fail.
+    #[inline]
+    pub fn type_hint(&mut self, type_hint: impl Into<TypeHint>) -> &mut Self {
+        self.type_hint = Some(type_hint.into());
+        self
+    }
+
+    pub(crate) fn declare(&self, ce: *mut zend_class_entry) -> Result<(), Box<dyn Error>> {
+        let value = scalar_to_z_val(&self.value);
+        let type_mask = self
+            .type_hint
+            .as_ref()
+            .map(|type_hint| type_hint.type_mask())
+            .unwrap_or_default();
+        let class_name = self
+            .type_hint
+            .as_ref()
+            .and_then(|type_hint| type_hint.class_name());
+
+        let declared = unsafe {
+            phper_zend_declare_class_constant(
+                ce,
+                self.name.as_ptr().cast(),
+                self.name.len(),
+                value.as_ptr(),
+                self.flags,
+                type_mask,
+                class_name
+                    .as_ref()
+                    .map(|name| name.as_ptr())
+                    .unwrap_or(null()),
+            )
+        };
+        if declared {
+            Ok(())
+        } else {
+            Err(format!(
+                "Typed class constant {} requires PHP 8.3 or later",
+                self.name
+            )
+            .into())
+        }
+    }
+}
+
 /// Builder for declare class property.
-struct PropertyEntity {
+pub struct PropertyEntity {
     name: String,
     visibility: RawVisibility,
-    value: Scalar,
+    value: Option<PropertyDefault>,
+    type_hint: Option<TypeHint>,
 }
 
 impl PropertyEntity {
-    fn new(name: impl Into<String>, visibility: Visibility, value: impl Into<Scalar>) -> Self {
+    fn new(
+        name: impl Into<String>,
+        visibility: Visibility,
+        value: Option<PropertyDefault>,
+    ) -> Self {
         Self {
             name: name.into(),
             visibility: visibility as RawVisibility,
-            value: value.into(),
+            value,
+            type_hint: None,
         }
     }
 
@@ -289,13 +438,87 @@ impl PropertyEntity {
         self
     }
 
-    pub(crate) fn declare(&self, ce: *mut zend_class_entry) {
+    /// Declare the type of property, like `public ?int $foo = null;`.
+    #[inline]
+    pub fn type_hint(&mut self, type_hint: impl Into<TypeHint>) -> &mut Self {
+        self.type_hint = Some(type_hint.into());
+        self
+    }
+
+    /// Set the default value, which can be array or constant expression, see
+    /// [PropertyDefault].
+    #[inline]
+    pub fn default_value(&mut self, value: impl Into<PropertyDefault>) -> &mut Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Declare the property as `readonly`, which can only be initialized once
+    /// inside the class scope.
+    ///
+    /// The readonly property should be typed, non-static and without default
+    /// value, as [`ClassEntity::add_typed_property`] declared, otherwise the
+    /// module startup fails.
+    #[inline]
+    pub fn set_readonly(&mut self) -> &mut Self {
+        self.visibility |= ZEND_ACC_READONLY;
+        self
+    }
+
+    pub(crate) fn declare(&self, ce: *mut zend_class_entry) -> Result<(), Box<dyn Error>> {
+        if self.visibility & ZEND_ACC_READONLY != 0
+            && (self.type_hint.is_none()
+                || self.value.is_some()
+                || self.visibility & ZEND_ACC_STATIC != 0)
+        {
+            return Err(format!(
+                "Readonly property {} should be typed, non-static and without default value",
+                self.name
+            )
+            .into());
+        }
+
+        match (&self.type_hint, &self.value) {
+            (None, Some(PropertyDefault::Scalar(value))) => {
+                self.declare_untyped(ce, value);
+                Ok(())
+            }
+            (type_hint, value) => {
+                let value = value.as_ref().map(|value| value.to_z_val(ce)).transpose()?;
+                let type_mask = type_hint
+                    .as_ref()
+                    .map(|type_hint| type_hint.type_mask())
+                    .unwrap_or_default();
+                let class_name = type_hint
+                    .as_ref()
+                    .and_then(|type_hint| type_hint.class_name());
+
+                unsafe {
+                    phper_zend_declare_typed_property(
+                        ce,
+                        self.name.as_ptr().cast(),
+                        self.name.len(),
+                        value.as_ref().map(|value| value.as_ptr()).unwrap_or(null()),
+                        self.visibility,
+                        type_mask,
+                        class_name
+                            .as_ref()
+                            .map(|name| name.as_ptr())
+                            .unwrap_or(null()),
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn declare_untyped(&self, ce: *mut zend_class_entry, value: &Scalar) {
         let name = self.name.as_ptr().cast();
         let name_length = self.name.len();
         let access_type = self.visibility as i32;
 
         unsafe {
-            match &self.value {
+            match value {
                 Scalar::Null => {
                     zend_declare_property_null(ce, name, name_length, access_type);
                 }
@@ -335,6 +558,137 @@ impl PropertyEntity {
     }
 }
 
+/// Default value of class property, besides the [Scalar], the array and the
+/// constant expression are supported.
+///
+/// The values are converted into persistent and immutable values when the
+/// class is registered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{
+///     classes::{ClassEntity, PropertyDefault, Visibility},
+///     types::BuiltinType,
+/// };
+///
+/// let mut class = ClassEntity::new("Foo");
+/// class.add_constant("LIMIT", 10, Visibility::Public);
+/// // public array $items = [];
+/// class
+///     .add_typed_property("items", Visibility::Public, BuiltinType::Array)
+///     .default_value(PropertyDefault::List(vec![]));
+/// // public $options = ['limit' => self::LIMIT, 'tags' => ['a', 'b']];
+/// class.add_property("options", Visibility::Public, ()).default_value(PropertyDefault::Map(vec![
+///     ("limit".to_owned(), PropertyDefault::Constant("self::LIMIT".to_owned())),
+///     ("tags".to_owned(), PropertyDefault::List(vec!["a".into(), "b".into()])),
+/// ]));
+/// ```
+pub enum PropertyDefault {
+    /// Scalar value.
+    Scalar(Scalar),
+
+    /// Array with the auto-increment keys, like `[1, 2]`.
+    List(Vec<PropertyDefault>),
+
+    /// Array with the string keys, like `['a' => 1]`.
+    Map(Vec<(String, PropertyDefault)>),
+
+    /// Constant expression referring to the constant, like `self::FOO`,
+    /// `Foo::BAR` or `PHP_EOL`, which is evaluated when the class is
+    /// registered, so the referred constants should be declared before.
+    Constant(String),
+}
+
+impl PropertyDefault {
+    /// Converts to the temporary zval, the strings and arrays will be
+    /// converted into persistent values when declaring.
+    fn to_z_val(&self, ce: *mut zend_class_entry) -> Result<ZVal, Box<dyn Error>> {
+        Ok(match self {
+            Self::Scalar(value) => scalar_to_z_val(value),
+            Self::List(items) => {
+                let mut arr = ZArray::new();
+                for item in items {
+                    arr.insert(InsertKey::NextIndex, item.to_z_val(ce)?);
+                }
+                arr.into()
+            }
+            Self::Map(items) => {
+                let mut arr = ZArray::new();
+                for (key, item) in items {
+                    arr.insert(key.as_str(), item.to_z_val(ce)?);
+                }
+                arr.into()
+            }
+            Self::Constant(expr) => unsafe {
+                let value = match expr.split_once("::") {
+                    Some((class_name, name)) => {
+                        let class_ce = if class_name.eq_ignore_ascii_case("self") {
+                            ce
+                        } else {
+                            find_global_class_entry_ptr(class_name.trim_start_matches('\\'))
+                        };
+                        if class_ce.is_null() {
+                            return Err(format!("Class \"{}\" not found", class_name).into());
+                        }
+                        phper_zend_get_class_constant_value(
+                            class_ce,
+                            name.as_ptr().cast(),
+                            name.len(),
+                        )
+                    }
+                    None => zend_get_constant_str(expr.as_ptr().cast(), expr.len()),
+                };
+                match ZVal::try_from_ptr(value) {
+                    Some(value)
+                        if value.get_type_info().get_base_type().into_raw() != IS_CONSTANT_AST =>
+                    {
+                        value.clone()
+                    }
+                    _ => return Err(format!("Undefined constant {}", expr).into()),
+                }
+            },
+        })
+    }
+}
+
+impl From<Scalar> for PropertyDefault {
+    fn from(value: Scalar) -> Self {
+        Self::Scalar(value)
+    }
+}
+
+impl From<&str> for PropertyDefault {
+    fn from(s: &str) -> Self {
+        Self::Scalar(s.into())
+    }
+}
+
+impl From<i64> for PropertyDefault {
+    fn from(i: i64) -> Self {
+        Self::Scalar(i.into())
+    }
+}
+
+impl From<Vec<PropertyDefault>> for PropertyDefault {
+    fn from(items: Vec<PropertyDefault>) -> Self {
+        Self::List(items)
+    }
+}
+
+/// Converts to the temporary zval, the strings will be interned as persistent
+/// when declaring.
+fn scalar_to_z_val(value: &Scalar) -> ZVal {
+    match value {
+        Scalar::Null => ZVal::from(()),
+        Scalar::Bool(b) => ZVal::from(*b),
+        Scalar::I64(i) => ZVal::from(*i),
+        Scalar::F64(f) => ZVal::from(*f),
+        Scalar::String(s) => ZVal::from(s.as_str()),
+        Scalar::Bytes(b) => ZVal::from(b.as_slice()),
+    }
+}
+
 /// Visibility of class properties and methods.
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
@@ -354,42 +708,34 @@ pub enum Visibility {
 pub(crate) type RawVisibility = u32;
 
 unsafe extern "C" fn create_object(ce: *mut zend_class_entry) -> *mut zend_object {
+    // The `create_object` is inherited by the userland subclasses, so the
+    // handlers are found by the ancestor.
+    let class_handlers = match ClassHandlers::find(ce) {
+        Some(class_handlers) => class_handlers,
+        None => {
+            // The engine requires an object, so create the one which can't be
+            // initialized.
+            let class_name = ZStr::from_ptr((*ce).name).to_str().unwrap_or_default();
+            throw(NotInitializedError::new(class_name.to_owned()));
+            ClassHandlers::unregistered()
+        }
+    };
+
     // Alloc more memory size to store state data.
     let state_object = phper_zend_object_alloc(size_of::<StateObj>(), ce);
     let state_object = StateObj::from_mut_ptr(state_object);
 
-    // Find the hack elements hidden behind null builtin_function.
-    let mut func_ptr = (*ce).info.internal.builtin_functions;
-    while !(*func_ptr).fname.is_null() {
-        func_ptr = func_ptr.offset(1);
-    }
-
-    // Get state constructor.
-    func_ptr = func_ptr.offset(1);
-    let state_constructor = func_ptr as *mut *const StateConstructor;
-    let state_constructor = state_constructor.read().as_ref().unwrap();
-
-    // Get state cloner.
-    func_ptr = func_ptr.offset(1);
-    let has_state_cloner =
-        slice::from_raw_parts(func_ptr as *const u8, size_of::<*const StateCloner>())
-            != [0u8; size_of::<*const StateCloner>()];
-
     // Common initialize process.
     let object = state_object.as_mut_object().as_mut_ptr();
     zend_object_std_init(object, ce);
     object_properties_init(object, ce);
     rebuild_object_properties(object);
 
-    // Set handlers
-    let mut handlers = Box::new(std_object_handlers);
-    handlers.offset = StateObj::offset() as c_int;
-    handlers.free_obj = Some(free_object);
-    handlers.clone_obj = has_state_cloner.then_some(clone_object);
-    (*object).handlers = Box::into_raw(handlers);
+    // Set handlers, which are shared by the objects of class.
+    (*object).handlers = class_handlers.initial().as_handlers_ptr();
 
     // Call the state constructor and store the state.
-    let data = (state_constructor)();
+    let data = (class_handlers.state_constructor)();
     *state_object.as_mut_any_state() = data;
 
     object
@@ -406,16 +752,9 @@ unsafe fn clone_object_common(object: *mut zend_object) -> *mut zend_object {
     let new_state_object = phper_zend_object_alloc(size_of::<StateObj>(), ce);
     let new_state_object = StateObj::from_mut_ptr(new_state_object);
 
-    // Find the hack elements hidden behind null builtin_function.
-    let mut func_ptr = (*(*object).ce).info.internal.builtin_functions;
-    while !(*func_ptr).fname.is_null() {
-        func_ptr = func_ptr.offset(1);
-    }
-
-    // Get state cloner.
-    func_ptr = func_ptr.offset(2);
-    let state_cloner = func_ptr as *mut *const StateCloner;
-    let state_cloner = state_cloner.read().as_ref().unwrap();
+    // Get state cloner, the `clone_obj` handler is only set with it.
+    let class_handlers = ClassHandlers::from_object(object);
+    let state_cloner = class_handlers.state_cloner.as_ref().unwrap();
 
     // Initialize and clone members
     let new_object = new_state_object.as_mut_object().as_mut_ptr();
@@ -434,6 +773,17 @@ unsafe fn clone_object_common(object: *mut zend_object) -> *mut zend_object {
     new_object
 }
 
+/// Whether the object is created by `phper`, and its state isn't built by the
+/// constructor yet.
+pub(crate) unsafe fn is_uninitialized(object: &ZObj) -> bool {
+    let free_obj = (*object.as_ptr())
+        .handlers
+        .as_ref()
+        .and_then(|handlers| handlers.free_obj);
+    free_obj.map(|f| f as usize) == Some(free_object as usize)
+        && !object.as_state_obj().is_initialized()
+}
+
 unsafe extern "C" fn free_object(object: *mut zend_object) {
     let state_object = StateObj::from_mut_object_ptr(object);
 
@@ -0,0 +1,330 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the native enums, which are supported since PHP 8.1.
+
+use crate::{
+    classes::{entry::ClassEntry, ConstantEntity, Visibility},
+    errors::Throwable,
+    functions::{Function, FunctionEntry, MethodEntity, ObjectMethod},
+    objects::{ZObj, ZObject},
+    sys::*,
+    types::Scalar,
+    utils::ensure_end_with_zero,
+    values::ZVal,
+};
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    mem::zeroed,
+    ptr::{null, null_mut},
+    rc::Rc,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for () {}
+
+    impl Sealed for i64 {}
+
+    impl Sealed for String {}
+}
+
+/// The backing type of enum, `()` for pure enum, `i64` and `String` for backed
+/// enum.
+pub trait EnumBackingType: private::Sealed + Into<Scalar> + 'static {
+    #[doc(hidden)]
+    const TYPE: u32;
+}
+
+impl EnumBackingType for () {
+    const TYPE: u32 = IS_UNDEF;
+}
+
+impl EnumBackingType for i64 {
+    const TYPE: u32 = IS_LONG;
+}
+
+impl EnumBackingType for String {
+    const TYPE: u32 = IS_STRING;
+}
+
+/// The [StaticEnum] holds [zend_class_entry] of enum, always as the static
+/// variable, and then be bind to [EnumEntity].
+///
+/// When the enum registered (module initialized), the [StaticEnum] will be
+/// initialized.
+///
+/// So, You shouldn't use [StaticEnum] in `module_init` stage, because it hasn't
+/// initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// use phper::enums::{EnumEntity, StaticEnum};
+///
+/// pub static SUIT_ENUM: StaticEnum = StaticEnum::null();
+///
+/// fn make_suit_enum() -> EnumEntity {
+///     let mut suit = EnumEntity::new("Suit");
+///     suit.bind(&SUIT_ENUM);
+///     suit
+/// }
+/// ```
+#[repr(transparent)]
+pub struct StaticEnum {
+    inner: AtomicPtr<zend_class_entry>,
+}
+
+impl StaticEnum {
+    /// Create empty [StaticEnum], with null [zend_class_entry].
+    pub const fn null() -> Self {
+        Self {
+            inner: AtomicPtr::new(null_mut()),
+        }
+    }
+
+    fn bind(&'static self, ptr: *mut zend_class_entry) {
+        self.inner.store(ptr, Ordering::Relaxed);
+    }
+
+    /// Converts to class entry.
+    pub fn as_class_entry(&'static self) -> &'static ClassEntry {
+        unsafe { ClassEntry::from_mut_ptr(self.inner.load(Ordering::Relaxed)) }
+    }
+
+    /// Get the case object by name, see [ClassEntry::get_enum_case].
+    pub fn get_case(&'static self, case_name: impl AsRef<str>) -> crate::Result<ZObject> {
+        self.as_class_entry().get_enum_case(case_name)
+    }
+}
+
+/// Builder for registering enum.
+///
+/// The type parameter `B` is the backing type, see [EnumBackingType].
+///
+/// # Examples
+///
+/// ```rust
+/// use phper::{classes::Visibility, enums::EnumEntity};
+///
+/// let mut status = EnumEntity::<i64>::new("Status");
+/// status.add_case("Active", 1);
+/// status.add_case("Inactive", 0);
+/// status.add_method("label", Visibility::Public, |this, _| {
+///     let name = this.get_enum_case_name().unwrap().to_str()?.to_owned();
+///     Ok::<_, phper::Error>(name.to_lowercase())
+/// });
+/// ```
+///
+/// Similarly in PHP:
+///
+/// ```php
+/// <?php
+///
+/// enum Status: int {
+///     case Active = 1;
+///     case Inactive = 0;
+///
+///     public function label() {
+///         return strtolower($this->name);
+///     }
+/// }
+/// ```
+pub struct EnumEntity<B: EnumBackingType = ()> {
+    enum_name: CString,
+    cases: Vec<(String, Scalar)>,
+    method_entities: Vec<MethodEntity>,
+    constant_entities: Vec<ConstantEntity>,
+    interfaces: Vec<Box<dyn Fn() -> &'static ClassEntry>>,
+    bind_enum: Option<&'static StaticEnum>,
+    _p: PhantomData<(B, *mut ())>,
+}
+
+impl<B: EnumBackingType> EnumEntity<B> {
+    /// Construct a new `EnumEntity` with enum name.
+    pub fn new(enum_name: impl AsRef<str>) -> Self {
+        Self {
+            enum_name: ensure_end_with_zero(enum_name),
+            cases: Vec::new(),
+            method_entities: Vec::new(),
+            constant_entities: Vec::new(),
+            interfaces: Vec::new(),
+            bind_enum: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Add case to enum, the `value` is `()` for pure enum.
+    ///
+    /// The values of backed enum should be unique, otherwise the module
+    /// startup fails.
+    pub fn add_case(&mut self, name: impl Into<String>, value: impl Into<B>) -> &mut Self {
+        let value: B = value.into();
+        self.cases.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add constant to enum, with visibility.
+    pub fn add_constant(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<Scalar>,
+        vis: Visibility,
+    ) -> &mut ConstantEntity {
+        self.constant_entities
+            .push(ConstantEntity::new(name, value, vis));
+        self.constant_entities.last_mut().unwrap()
+    }
+
+    /// Add member method to enum, with visibility and method handler.
+    ///
+    /// The `$this` is the case object, which has no state.
+    pub fn add_method<F, Z, E>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut ZObj, &mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.method_entities.push(MethodEntity::new(
+            name,
+            Some(Rc::new(ObjectMethod::new(handler))),
+            vis,
+        ));
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add static method to enum, with visibility and method handler.
+    pub fn add_static_method<F, Z, E>(
+        &mut self,
+        name: impl AsRef<str>,
+        vis: Visibility,
+        handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        let mut entity = MethodEntity::new(name, Some(Rc::new(Function::new(handler))), vis);
+        entity.set_vis_static();
+        self.method_entities.push(entity);
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Register enum to `implements` the interface, due to the enum can
+    /// implement multi interface, so this method can be called multi time.
+    ///
+    /// *Because in the `MINIT` phase, the class starts to register, so the*
+    /// *closure is used to return the `ClassEntry` to delay the acquisition of*
+    /// *the class.*
+    pub fn implements(&mut self, interface: impl Fn() -> &'static ClassEntry + 'static) {
+        self.interfaces.push(Box::new(interface));
+    }
+
+    /// Bind to static [StaticEnum].
+    ///
+    /// When the enum registered, the [StaticEnum] will be initialized, so you
+    /// can use the [StaticEnum] to get the case objects.
+    pub fn bind(&mut self, e: &'static StaticEnum) {
+        self.bind_enum = Some(e);
+    }
+
+    unsafe fn function_entries(&self) -> *const zend_function_entry {
+        let mut methods = self
+            .method_entities
+            .iter()
+            .map(|method| FunctionEntry::from_method_entity(method))
+            .collect::<Vec<_>>();
+
+        methods.push(zeroed::<zend_function_entry>());
+
+        Box::into_raw(methods.into_boxed_slice()).cast()
+    }
+
+    fn check_cases(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for (i, (name, value)) in self.cases.iter().enumerate() {
+            let duplicated = self.cases[..i]
+                .iter()
+                .find(|(_, prev)| match (prev, value) {
+                    (Scalar::I64(prev), Scalar::I64(value)) => prev == value,
+                    (Scalar::String(prev), Scalar::String(value)) => prev == value,
+                    _ => false,
+                });
+            if let Some((prev_name, _)) = duplicated {
+                return Err(format!(
+                    "Duplicate value in enum {} for cases {} and {}",
+                    self.enum_name.to_string_lossy(),
+                    prev_name,
+                    name
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B: EnumBackingType> crate::modules::Registerer for EnumEntity<B> {
+    fn register(&mut self, _: i32) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_cases()?;
+
+        unsafe {
+            let enum_ce = phper_register_enum_entry(
+                self.enum_name.as_ptr(),
+                B::TYPE,
+                self.function_entries(),
+            );
+            if enum_ce.is_null() {
+                return Err(format!(
+                    "Enum {} is supported since PHP 8.1",
+                    self.enum_name.to_string_lossy()
+                )
+                .into());
+            }
+
+            if let Some(bind_enum) = self.bind_enum {
+                bind_enum.bind(enum_ce);
+            }
+
+            for interface in &self.interfaces {
+                let interface_ce = interface().as_ptr();
+                zend_class_implements(enum_ce, 1, interface_ce);
+            }
+
+            for (name, value) in &self.cases {
+                let value = match value {
+                    Scalar::I64(i) => Some(ZVal::from(*i)),
+                    Scalar::String(s) => Some(ZVal::from(s.as_str())),
+                    _ => None,
+                };
+                phper_enum_add_case(
+                    enum_ce,
+                    name.as_ptr().cast(),
+                    name.len(),
+                    value.as_ref().map(|value| value.as_ptr()).unwrap_or(null()),
+                );
+            }
+
+            for constant in &self.constant_entities {
+                constant.declare(enum_ce)?;
+            }
+        }
+
+        Ok(())
+    }
+}
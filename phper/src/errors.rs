@@ -199,6 +199,11 @@ pub enum Error {
     #[error(transparent)]
     ArgumentCount(#[from] ArgumentCountError),
 
+    /// Throw when the argument can't be extracted to the parameter type of
+    /// typed handler.
+    #[error(transparent)]
+    ArgumentType(#[from] ArgumentTypeError),
+
     /// Failed to initialize object.
     #[error(transparent)]
     InitializeObject(#[from] InitializeObjectError),
@@ -210,6 +215,22 @@ pub enum Error {
     /// Failed when the object isn't implement PHP `Throwable`.
     #[error(transparent)]
     NotImplementThrowable(#[from] NotImplementThrowableError),
+
+    /// The value isn't a valid callback.
+    #[error(transparent)]
+    NotCallable(#[from] NotCallableError),
+
+    /// Enum case not found, get the case by name failed.
+    #[error(transparent)]
+    EnumCaseNotFound(#[from] EnumCaseNotFoundError),
+
+    /// The object is used before its state is built by the constructor.
+    #[error(transparent)]
+    NotInitialized(#[from] NotInitializedError),
+
+    /// The state of object isn't the expected type.
+    #[error(transparent)]
+    StateType(#[from] StateTypeError),
 }
 
 impl Error {
@@ -237,9 +258,14 @@ impl Throwable for Error {
             Error::Throw(e) => Throwable::get_class(e),
             Error::ClassNotFound(e) => Throwable::get_class(e),
             Error::ArgumentCount(e) => Throwable::get_class(e),
+            Error::ArgumentType(e) => Throwable::get_class(e),
             Error::InitializeObject(e) => Throwable::get_class(e),
             Error::ExpectType(e) => Throwable::get_class(e),
             Error::NotImplementThrowable(e) => Throwable::get_class(e),
+            Error::NotCallable(e) => Throwable::get_class(e),
+            Error::EnumCaseNotFound(e) => Throwable::get_class(e),
+            Error::NotInitialized(e) => Throwable::get_class(e),
+            Error::StateType(e) => Throwable::get_class(e),
         }
     }
 
@@ -253,9 +279,14 @@ impl Throwable for Error {
             Error::Throw(e) => Throwable::get_code(e),
             Error::ClassNotFound(e) => Throwable::get_code(e),
             Error::ArgumentCount(e) => Throwable::get_code(e),
+            Error::ArgumentType(e) => Throwable::get_code(e),
             Error::InitializeObject(e) => Throwable::get_code(e),
             Error::ExpectType(e) => Throwable::get_code(e),
             Error::NotImplementThrowable(e) => Throwable::get_code(e),
+            Error::NotCallable(e) => Throwable::get_code(e),
+            Error::EnumCaseNotFound(e) => Throwable::get_code(e),
+            Error::NotInitialized(e) => Throwable::get_code(e),
+            Error::StateType(e) => Throwable::get_code(e),
         }
     }
 
@@ -268,9 +299,14 @@ impl Throwable for Error {
             Error::Throw(e) => Throwable::get_message(e),
             Error::ClassNotFound(e) => Throwable::get_message(e),
             Error::ArgumentCount(e) => Throwable::get_message(e),
+            Error::ArgumentType(e) => Throwable::get_message(e),
             Error::InitializeObject(e) => Throwable::get_message(e),
             Error::ExpectType(e) => Throwable::get_message(e),
             Error::NotImplementThrowable(e) => Throwable::get_message(e),
+            Error::NotCallable(e) => Throwable::get_message(e),
+            Error::EnumCaseNotFound(e) => Throwable::get_message(e),
+            Error::NotInitialized(e) => Throwable::get_message(e),
+            Error::StateType(e) => Throwable::get_message(e),
         }
     }
 
@@ -283,9 +319,14 @@ impl Throwable for Error {
             Error::Throw(e) => Throwable::to_object(e),
             Error::ClassNotFound(e) => Throwable::to_object(e),
             Error::ArgumentCount(e) => Throwable::to_object(e),
+            Error::ArgumentType(e) => Throwable::to_object(e),
             Error::InitializeObject(e) => Throwable::to_object(e),
             Error::ExpectType(e) => Throwable::to_object(e),
             Error::NotImplementThrowable(e) => Throwable::to_object(e),
+            Error::NotCallable(e) => Throwable::to_object(e),
+            Error::EnumCaseNotFound(e) => Throwable::to_object(e),
+            Error::NotInitialized(e) => Throwable::to_object(e),
+            Error::StateType(e) => Throwable::to_object(e),
         }
     }
 }
@@ -443,6 +484,24 @@ impl Throwable for ArgumentCountError {
     }
 }
 
+/// Throw when the argument can't be extracted to the parameter type of typed
+/// handler.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{function_name}(): Argument #{position} (${name}) must be of type {expect_type}, {given_type} given")]
+pub struct ArgumentTypeError {
+    function_name: String,
+    position: usize,
+    name: String,
+    expect_type: String,
+    given_type: String,
+}
+
+impl Throwable for ArgumentTypeError {
+    fn get_class(&self) -> &ClassEntry {
+        type_error_class()
+    }
+}
+
 /// Failed to initialize object.
 #[derive(Debug, thiserror::Error, Constructor)]
 #[error("Cannot instantiate class {class_name}")]
@@ -467,6 +526,62 @@ impl Throwable for NotImplementThrowableError {
     }
 }
 
+/// The value isn't a valid callback.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Expected a valid callback, {reason}")]
+pub struct NotCallableError {
+    reason: String,
+}
+
+impl Throwable for NotCallableError {
+    fn get_class(&self) -> &ClassEntry {
+        type_error_class()
+    }
+}
+
+/// Enum case not found, get the case by name failed.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Undefined enum case {enum_name}::{case_name}")]
+pub struct EnumCaseNotFoundError {
+    enum_name: String,
+    case_name: String,
+}
+
+impl Throwable for EnumCaseNotFoundError {
+    fn get_class(&self) -> &ClassEntry {
+        error_class()
+    }
+}
+
+/// The object is used before its state is built by the constructor, see
+/// [ClassEntity::new_with_constructor](crate::classes::entity::ClassEntity::new_with_constructor).
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Object of class {class_name} is not initialized")]
+pub struct NotInitializedError {
+    class_name: String,
+}
+
+impl Throwable for NotInitializedError {
+    fn get_class(&self) -> &ClassEntry {
+        error_class()
+    }
+}
+
+/// The state of object isn't the expected type, see
+/// [StateObj::try_as_state](crate::objects::StateObj::try_as_state).
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("State of object of class {class_name} is not {state_type}")]
+pub struct StateTypeError {
+    class_name: String,
+    state_type: &'static str,
+}
+
+impl Throwable for StateTypeError {
+    fn get_class(&self) -> &ClassEntry {
+        error_class()
+    }
+}
+
 /// Guarder for preventing the thrown exception from being overwritten.
 ///
 /// Normally, you don't need to use `ExceptionGuard`, unless before you call the
@@ -13,20 +13,29 @@
 //! TODO Add lambda.
 
 use crate::{
-    classes::{entry::ClassEntry, RawVisibility, Visibility},
-    errors::{throw, ArgumentCountError, ExceptionGuard, ThrowObject, Throwable},
+    arrays::ZArr,
+    classes::{
+        entry::ClassEntry, handlers::ClassHandlers, is_uninitialized, RawVisibility, Visibility,
+    },
+    errors::{
+        throw, ArgumentCountError, ArgumentTypeError, ExceptionGuard, NotCallableError,
+        NotInitializedError, ThrowObject, Throwable,
+    },
     objects::{StateObj, ZObj, ZObject},
     strings::{ZStr, ZString},
     sys::*,
+    types::TypeHint,
     utils::ensure_end_with_zero,
-    values::{ExecuteData, ZVal},
+    values::{ExecuteData, FromZVal, IntoZVal, TypedArgument, ZVal},
 };
 use phper_alloc::ToRefOwned;
 use std::{
     ffi::{CStr, CString},
+    fmt,
     marker::PhantomData,
-    mem::{transmute, zeroed},
-    ptr::{self, null_mut},
+    mem::{self, transmute, ManuallyDrop},
+    os::raw::c_void,
+    ptr::{self, null, null_mut},
     rc::Rc,
 };
 
@@ -98,6 +107,484 @@ where
     }
 }
 
+/// The method whose `$this` isn't created by phper, like the enum case, so the
+/// state can't be taken.
+pub(crate) struct ObjectMethod<F, Z, E>(F, PhantomData<(Z, E)>);
+
+impl<F, Z, E> ObjectMethod<F, Z, E> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, Z, E> Callable for ObjectMethod<F, Z, E>
+where
+    F: Fn(&mut ZObj, &mut [ZVal]) -> Result<Z, E>,
+    Z: Into<ZVal>,
+    E: Throwable,
+{
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        let this = execute_data.get_this_mut().unwrap();
+        match (self.0)(this, arguments) {
+            Ok(z) => {
+                *return_value = z.into();
+            }
+            Err(e) => {
+                unsafe {
+                    throw(e);
+                }
+                *return_value = ().into();
+            }
+        }
+    }
+}
+
+pub(crate) struct VariadicFunction<F, Z, E>(F, PhantomData<(Z, E)>);
+
+impl<F, Z, E> VariadicFunction<F, Z, E> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, Z, E> Callable for VariadicFunction<F, Z, E>
+where
+    F: Fn(&mut [ZVal], &mut [ZVal], &ZArr) -> Result<Z, E>,
+    Z: Into<ZVal>,
+    E: Throwable,
+{
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        let (arguments, variadic_arguments) = split_variadic_arguments(execute_data, arguments);
+        let named_arguments = unsafe { extra_named_arguments(execute_data) };
+        match (self.0)(arguments, variadic_arguments, named_arguments) {
+            Ok(z) => {
+                *return_value = z.into();
+            }
+            Err(e) => {
+                unsafe {
+                    throw(e);
+                }
+                *return_value = ().into();
+            }
+        }
+    }
+}
+
+pub(crate) struct VariadicMethod<F, Z, E>(F, PhantomData<(Z, E)>);
+
+impl<F, Z, E> VariadicMethod<F, Z, E> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, Z, E> Callable for VariadicMethod<F, Z, E>
+where
+    F: Fn(&mut StateObj, &mut [ZVal], &mut [ZVal], &ZArr) -> Result<Z, E>,
+    Z: Into<ZVal>,
+    E: Throwable,
+{
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        let (arguments, variadic_arguments) = split_variadic_arguments(execute_data, arguments);
+        let named_arguments = unsafe { extra_named_arguments(execute_data) };
+        let this = unsafe { execute_data.get_this_mut().unwrap().as_mut_state_obj() };
+        match (self.0)(this, arguments, variadic_arguments, named_arguments) {
+            Ok(z) => {
+                *return_value = z.into();
+            }
+            Err(e) => {
+                unsafe {
+                    throw(e);
+                }
+                *return_value = ().into();
+            }
+        }
+    }
+}
+
+/// Split the arguments into the declared arguments and the values collected
+/// by the variadic argument.
+fn split_variadic_arguments<'a>(
+    execute_data: &ExecuteData,
+    arguments: &'a mut [ZVal],
+) -> (&'a mut [ZVal], &'a mut [ZVal]) {
+    let num_args = (execute_data.common_num_args() as usize).min(arguments.len());
+    arguments.split_at_mut(num_args)
+}
+
+/// Gets the extra named arguments collected by the variadic argument, the
+/// lifetime is unbounded because it is owned by the call frame.
+unsafe fn extra_named_arguments<'a>(execute_data: &ExecuteData) -> &'a ZArr {
+    let named_arguments = phper_zend_call_extra_named_params(execute_data.as_ptr());
+    if named_arguments.is_null() {
+        ZArr::from_ptr(ptr::addr_of!(zend_empty_array))
+    } else {
+        ZArr::from_ptr(named_arguments)
+    }
+}
+
+/// Handler of function with typed arguments and return value, implemented
+/// for the closures such as `Fn(i64, &str, Option<&ZArr>) -> Result<String,
+/// E>`, whose arguments implement [`TypedArgument`] and the returned value
+/// implements [`IntoZVal`].
+///
+/// The arguments and return type are inferred from the closure signature, see
+/// [`Module::add_typed_function`](crate::modules::Module::add_typed_function).
+///
+/// The closure must accept the borrowed arguments with any lifetime, so they
+/// can't be leaked out of the handler.
+pub trait TypedFunctionHandler<Args>: 'static {
+    #[doc(hidden)]
+    fn arguments() -> Vec<Argument>;
+
+    #[doc(hidden)]
+    fn return_type() -> Option<TypeHint>;
+
+    #[doc(hidden)]
+    fn call(&self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal);
+}
+
+/// Handler of method with typed arguments and return value, like
+/// [`TypedFunctionHandler`], but the first argument of closure is `&mut
+/// StateObj`.
+pub trait TypedMethodHandler<Args>: 'static {
+    #[doc(hidden)]
+    fn arguments() -> Vec<Argument>;
+
+    #[doc(hidden)]
+    fn return_type() -> Option<TypeHint>;
+
+    #[doc(hidden)]
+    fn call(&self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal);
+}
+
+/// Handler of constructor with typed arguments, like [`TypedFunctionHandler`],
+/// but the returned value is the state of object, see
+/// [`ClassEntity::new_with_typed_constructor`](crate::classes::entity::ClassEntity::new_with_typed_constructor).
+pub trait TypedConstructorHandler<Args>: 'static {
+    #[doc(hidden)]
+    fn arguments() -> Vec<Argument>;
+
+    #[doc(hidden)]
+    fn call(&self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal);
+}
+
+macro_rules! impl_typed_handlers {
+    ($($arg:ident),*) => {
+        impl<F, T, E, $($arg,)*> TypedFunctionHandler<(T, E, $($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Result<T, E>
+                + for<'a> Fn($($arg::Borrowed<'a>),*) -> Result<T, E>
+                + 'static,
+            T: IntoZVal,
+            E: Throwable,
+            $($arg: TypedArgument,)*
+        {
+            fn arguments() -> Vec<Argument> {
+                typed_arguments(&[$((
+                    <$arg::Borrowed<'static>>::type_hint(),
+                    <$arg::Borrowed<'static>>::is_optional(),
+                )),*])
+            }
+
+            fn return_type() -> Option<TypeHint> {
+                T::type_hint()
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn call(
+                &self,
+                execute_data: &mut ExecuteData,
+                arguments: &mut [ZVal],
+                return_value: &mut ZVal,
+            ) {
+                let mut position = 0;
+                $(
+                    position += 1;
+                    let $arg = match unsafe {
+                        extract_argument::<$arg::Borrowed<'_>>(execute_data, arguments, position)
+                    } {
+                        Ok(arg) => arg,
+                        Err(e) => {
+                            unsafe {
+                                throw(e);
+                            }
+                            *return_value = ().into();
+                            return;
+                        }
+                    };
+                )*
+                set_typed_return_value(self($($arg),*), return_value);
+            }
+        }
+
+        impl<F, T, E, $($arg,)*> TypedMethodHandler<(T, E, $($arg,)*)> for F
+        where
+            F: Fn(&mut StateObj, $($arg),*) -> Result<T, E>
+                + for<'a> Fn(&mut StateObj, $($arg::Borrowed<'a>),*) -> Result<T, E>
+                + 'static,
+            T: IntoZVal,
+            E: Throwable,
+            $($arg: TypedArgument,)*
+        {
+            fn arguments() -> Vec<Argument> {
+                typed_arguments(&[$((
+                    <$arg::Borrowed<'static>>::type_hint(),
+                    <$arg::Borrowed<'static>>::is_optional(),
+                )),*])
+            }
+
+            fn return_type() -> Option<TypeHint> {
+                T::type_hint()
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn call(
+                &self,
+                execute_data: &mut ExecuteData,
+                arguments: &mut [ZVal],
+                return_value: &mut ZVal,
+            ) {
+                let mut position = 0;
+                $(
+                    position += 1;
+                    let $arg = match unsafe {
+                        extract_argument::<$arg::Borrowed<'_>>(execute_data, arguments, position)
+                    } {
+                        Ok(arg) => arg,
+                        Err(e) => {
+                            unsafe {
+                                throw(e);
+                            }
+                            *return_value = ().into();
+                            return;
+                        }
+                    };
+                )*
+                let this = unsafe { execute_data.get_this_mut().unwrap().as_mut_state_obj() };
+                set_typed_return_value(self(this, $($arg),*), return_value);
+            }
+        }
+
+        impl<F, T, E, $($arg,)*> TypedConstructorHandler<(T, E, $($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Result<T, E>
+                + for<'a> Fn($($arg::Borrowed<'a>),*) -> Result<T, E>
+                + 'static,
+            T: 'static,
+            E: Throwable,
+            $($arg: TypedArgument,)*
+        {
+            fn arguments() -> Vec<Argument> {
+                typed_arguments(&[$((
+                    <$arg::Borrowed<'static>>::type_hint(),
+                    <$arg::Borrowed<'static>>::is_optional(),
+                )),*])
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn call(
+                &self,
+                execute_data: &mut ExecuteData,
+                arguments: &mut [ZVal],
+                return_value: &mut ZVal,
+            ) {
+                *return_value = ().into();
+                let mut position = 0;
+                $(
+                    position += 1;
+                    let $arg = match unsafe {
+                        extract_argument::<$arg::Borrowed<'_>>(execute_data, arguments, position)
+                    } {
+                        Ok(arg) => arg,
+                        Err(e) => {
+                            unsafe {
+                                throw(e);
+                            }
+                            return;
+                        }
+                    };
+                )*
+                let state = self($($arg),*);
+                let this = unsafe { execute_data.get_this_mut().unwrap().as_mut_state_obj() };
+                match state {
+                    Ok(state) => unsafe { ClassHandlers::initialize(this, Box::new(state)) },
+                    Err(e) => unsafe { throw(e) },
+                }
+            }
+        }
+    };
+}
+
+impl_typed_handlers!();
+impl_typed_handlers!(A1);
+impl_typed_handlers!(A1, A2);
+impl_typed_handlers!(A1, A2, A3);
+impl_typed_handlers!(A1, A2, A3, A4);
+impl_typed_handlers!(A1, A2, A3, A4, A5);
+impl_typed_handlers!(A1, A2, A3, A4, A5, A6);
+impl_typed_handlers!(A1, A2, A3, A4, A5, A6, A7);
+impl_typed_handlers!(A1, A2, A3, A4, A5, A6, A7, A8);
+
+pub(crate) struct TypedFunction<H, Args>(H, PhantomData<fn() -> Args>);
+
+impl<H, Args> TypedFunction<H, Args> {
+    pub(crate) fn new(handler: H) -> Self {
+        Self(handler, PhantomData)
+    }
+}
+
+impl<H: TypedFunctionHandler<Args>, Args> Callable for TypedFunction<H, Args> {
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        self.0.call(execute_data, arguments, return_value)
+    }
+}
+
+pub(crate) struct TypedMethod<H, Args>(H, PhantomData<fn() -> Args>);
+
+impl<H, Args> TypedMethod<H, Args> {
+    pub(crate) fn new(handler: H) -> Self {
+        Self(handler, PhantomData)
+    }
+}
+
+impl<H: TypedMethodHandler<Args>, Args> Callable for TypedMethod<H, Args> {
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        self.0.call(execute_data, arguments, return_value)
+    }
+}
+
+pub(crate) struct TypedConstructor<H, Args>(H, PhantomData<fn() -> Args>);
+
+impl<H, Args> TypedConstructor<H, Args> {
+    pub(crate) fn new(handler: H) -> Self {
+        Self(handler, PhantomData)
+    }
+}
+
+impl<H: TypedConstructorHandler<Args>, Args> Callable for TypedConstructor<H, Args> {
+    fn call(
+        &self,
+        execute_data: &mut ExecuteData,
+        arguments: &mut [ZVal],
+        return_value: &mut ZVal,
+    ) {
+        self.0.call(execute_data, arguments, return_value)
+    }
+}
+
+/// Infers the arguments of typed handler, named `arg1`, `arg2`, etc. The
+/// trailing optional arguments default to `null`, others are required.
+fn typed_arguments(params: &[(Option<TypeHint>, bool)]) -> Vec<Argument> {
+    let num_required = params
+        .iter()
+        .rposition(|(_, optional)| !optional)
+        .map_or(0, |i| i + 1);
+    params
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, (type_hint, _))| {
+            let name = format!("arg{}", i + 1);
+            let mut argument = if i < num_required {
+                Argument::by_val(name)
+            } else {
+                Argument::by_val_optional(name).with_default_value("null")
+            };
+            argument.type_hint = type_hint;
+            argument
+        })
+        .collect()
+}
+
+/// Extracts the typed argument at `position` (starts from 1).
+///
+/// # Safety
+///
+/// The lifetime `'a` is unbounded, the caller must not use the argument after
+/// calling, and must not extract the same position twice.
+unsafe fn extract_argument<'a, A: FromZVal<'a>>(
+    execute_data: &ExecuteData,
+    arguments: &mut [ZVal],
+    position: usize,
+) -> crate::Result<A> {
+    let function_name = || {
+        execute_data
+            .func()
+            .get_function_or_method_name()
+            .to_str()
+            .map(ToOwned::to_owned)
+            .unwrap_or_default()
+    };
+
+    if position > arguments.len() {
+        return Err(ArgumentCountError::new(function_name(), position, arguments.len()).into());
+    }
+    let val = &mut *arguments.as_mut_ptr().add(position - 1);
+    let given_type = val.get_type_info();
+
+    A::from_z_val(val).map_err(|_| {
+        let arg_info = execute_data.common_arg_info().add(position - 1);
+        // The names of internal function arguments are C strings.
+        let name = CStr::from_ptr((*arg_info).name.cast_const().cast())
+            .to_string_lossy()
+            .into_owned();
+        let expect_type = A::type_hint()
+            .map(|type_hint| type_hint.to_string())
+            .unwrap_or_else(|| "mixed".to_owned());
+        ArgumentTypeError::new(
+            function_name(),
+            position,
+            name,
+            expect_type,
+            given_type.to_string(),
+        )
+        .into()
+    })
+}
+
+fn set_typed_return_value<T: IntoZVal, E: Throwable>(
+    result: Result<T, E>,
+    return_value: &mut ZVal,
+) {
+    match result {
+        Ok(t) => {
+            *return_value = t.into_z_val();
+        }
+        Err(e) => {
+            unsafe {
+                throw(e);
+            }
+            *return_value = ().into();
+        }
+    }
+}
+
 /// Wrapper of [`zend_function_entry`].
 #[repr(transparent)]
 pub struct FunctionEntry {
@@ -110,6 +597,7 @@ impl FunctionEntry {
         Self::entry(
             &entity.name,
             &entity.arguments,
+            entity.return_type.as_ref(),
             Some(entity.handler.clone()),
             None,
         )
@@ -119,6 +607,7 @@ impl FunctionEntry {
         Self::entry(
             &entity.name,
             &entity.arguments,
+            entity.return_type.as_ref(),
             entity.handler.clone(),
             Some(entity.visibility),
         )
@@ -128,31 +617,24 @@ impl FunctionEntry {
     unsafe fn entry(
         name: &CStr,
         arguments: &[Argument],
+        return_type: Option<&TypeHint>,
         handler: Option<Rc<dyn Callable>>,
         visibility: Option<RawVisibility>,
     ) -> zend_function_entry {
-        let mut infos = Vec::new();
-
-        let require_arg_count = arguments.iter().filter(|arg| arg.required).count();
-        infos.push(phper_zend_begin_arg_info_ex(false, require_arg_count));
-
-        for arg in arguments {
-            infos.push(phper_zend_arg_info(
-                arg.pass_by_ref,
-                arg.name.as_ptr().cast(),
-            ));
-        }
-
-        infos.push(zeroed::<zend_internal_arg_info>());
+        // The names and default values are referenced by the engine until
+        // shutdown, but the entities may be dropped after registering.
+        let mut strings = Vec::new();
+        let mut infos = build_arg_infos(arguments, return_type, &mut strings);
+        mem::forget(strings);
 
         let raw_handler = handler.as_ref().map(|_| invoke as _);
 
+        // The arg infos will be copied by the engine when registering typed
+        // functions, only the arguments and the return info are kept, so the
+        // handler is hidden in the `default_value` of return info, which is
+        // never read by the engine.
         if let Some(handler) = handler {
-            let translator = CallableTranslator {
-                callable: Rc::into_raw(handler),
-            };
-            let last_arg_info: zend_internal_arg_info = translator.internal_arg_info;
-            infos.push(last_arg_info);
+            infos[0].default_value = Box::into_raw(Box::new(handler)).cast_const().cast();
         }
 
         let flags = visibility.unwrap_or(Visibility::default() as u32);
@@ -167,11 +649,138 @@ impl FunctionEntry {
     }
 }
 
+/// Builds the arg infos, the first one is the return info. The strings
+/// referenced by arg infos are pushed into `strings`, which should outlive the
+/// arg infos.
+unsafe fn build_arg_infos(
+    arguments: &[Argument],
+    return_type: Option<&TypeHint>,
+    strings: &mut Vec<CString>,
+) -> Vec<zend_internal_arg_info> {
+    let mut hold = |s: CString| {
+        let ptr = s.as_ptr();
+        strings.push(s);
+        ptr
+    };
+
+    let mut infos = Vec::with_capacity(arguments.len() + 1);
+
+    let require_arg_count = arguments.iter().filter(|arg| arg.required).count();
+    let return_info = match return_type {
+        Some(return_type) => phper_zend_begin_arg_with_return_type_info_ex(
+            false,
+            require_arg_count,
+            return_type.type_mask(),
+            return_type.class_name().map(&mut hold).unwrap_or(null()),
+        ),
+        None => phper_zend_begin_arg_info_ex(false, require_arg_count),
+    };
+    infos.push(return_info);
+
+    for arg in arguments {
+        let name = hold(arg.name.clone());
+        let mut info = match &arg.type_hint {
+            Some(type_hint) => phper_zend_arg_info_with_type(
+                arg.pass_by_ref,
+                arg.is_variadic,
+                name,
+                type_hint.type_mask(),
+                type_hint.class_name().map(&mut hold).unwrap_or(null()),
+            ),
+            None if arg.is_variadic => {
+                phper_zend_arg_info_with_type(arg.pass_by_ref, true, name, 0, null())
+            }
+            None => phper_zend_arg_info(arg.pass_by_ref, name),
+        };
+        if let Some(default_value) = &arg.default_value {
+            info.default_value = hold(default_value.clone());
+        }
+        infos.push(info);
+    }
+
+    infos
+}
+
+/// Data of closure created by [`ZVal::from_closure`], shared by the closures
+/// copied by cloning, `Closure::bind` and `Closure::bindTo`, and freed with the
+/// last of them. The handler and strings are only held for the ownership, and
+/// referenced by the engine.
+#[repr(C)]
+struct ClosureData {
+    // Should be the first field, because the pointer of data is hidden in the
+    // return info as the handler, see `invoke`.
+    _handler: Rc<dyn Callable>,
+    refcount: usize,
+    arg_infos: Vec<zend_internal_arg_info>,
+    _strings: Vec<CString>,
+}
+
+impl Drop for ClosureData {
+    fn drop(&mut self) {
+        for arg_info in &mut self.arg_infos {
+            unsafe {
+                phper_zend_arg_info_release_type(arg_info);
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn addref_closure_data(data: *mut c_void) {
+    (*data.cast::<ClosureData>()).refcount += 1;
+}
+
+unsafe extern "C" fn release_closure_data(data: *mut c_void) {
+    let data = data.cast::<ClosureData>();
+    (*data).refcount -= 1;
+    if (*data).refcount == 0 {
+        drop(Box::from_raw(data));
+    }
+}
+
+/// Hooks the copying of closures, called at the module startup.
+pub(crate) unsafe fn startup_closures() {
+    phper_zend_closures_startup(Some(addref_closure_data), Some(release_closure_data));
+}
+
+/// Restores the hooks of closures, called at the module shutdown.
+pub(crate) unsafe fn shutdown_closures() {
+    phper_zend_closures_shutdown();
+}
+
+pub(crate) fn new_closure(
+    handler: Rc<dyn Callable>,
+    arguments: &[Argument],
+    return_type: Option<&TypeHint>,
+) -> ZVal {
+    unsafe {
+        let mut strings = Vec::new();
+        let arg_infos = build_arg_infos(arguments, return_type, &mut strings);
+        let data = Box::into_raw(Box::new(ClosureData {
+            _handler: handler,
+            refcount: 1,
+            arg_infos,
+            _strings: strings,
+        }));
+        let arg_infos = (*data).arg_infos.as_mut_ptr();
+        (*arg_infos).default_value = data.cast_const().cast();
+
+        let mut val = ZVal::default();
+        phper_zend_create_closure(
+            val.as_mut_ptr(),
+            arg_infos,
+            arguments.len() as u32,
+            Some(invoke),
+        );
+        val
+    }
+}
+
 /// Builder for registering php function.
 pub struct FunctionEntity {
     name: CString,
     handler: Rc<dyn Callable>,
     arguments: Vec<Argument>,
+    return_type: Option<TypeHint>,
 }
 
 impl FunctionEntity {
@@ -181,9 +790,26 @@ impl FunctionEntity {
             name: ensure_end_with_zero(name),
             handler,
             arguments: Default::default(),
+            return_type: None,
         }
     }
 
+    /// Construct the function entity with typed handler, the arguments and
+    /// return type are inferred, see
+    /// [`Module::add_typed_function`](crate::modules::Module::add_typed_function).
+    ///
+    /// Usually generated by [`php_function`](crate::php_function).
+    pub fn new_typed<H, Args>(name: impl AsRef<str>, handler: H) -> Self
+    where
+        H: TypedFunctionHandler<Args>,
+        Args: 'static,
+    {
+        let mut entity = Self::new(name, Rc::new(TypedFunction::new(handler)));
+        entity.arguments = H::arguments();
+        entity.return_type = H::return_type();
+        entity
+    }
+
     /// Add single function argument info.
     #[inline]
     pub fn argument(&mut self, argument: Argument) -> &mut Self {
@@ -197,6 +823,27 @@ impl FunctionEntity {
         self.arguments.extend(arguments);
         self
     }
+
+    /// Rename the arguments in order, usually used to name the inferred
+    /// arguments of typed handler, which are named `arg1`, `arg2`, etc.
+    pub fn argument_names<S: AsRef<str>>(
+        &mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> &mut Self {
+        rename_arguments(&mut self.arguments, names);
+        self
+    }
+
+    /// Declare the return type of function.
+    ///
+    /// The returned value isn't checked by the engine for internal functions,
+    /// so it is only checked when PHP is built with debug mode (`ZEND_DEBUG`),
+    /// throwing `TypeError` if mismatched.
+    #[inline]
+    pub fn return_type(&mut self, return_type: impl Into<TypeHint>) -> &mut Self {
+        self.return_type = Some(return_type.into());
+        self
+    }
 }
 
 /// Builder for registering class method.
@@ -204,6 +851,7 @@ pub struct MethodEntity {
     name: CString,
     handler: Option<Rc<dyn Callable>>,
     arguments: Vec<Argument>,
+    return_type: Option<TypeHint>,
     visibility: RawVisibility,
 }
 
@@ -219,9 +867,61 @@ impl MethodEntity {
             handler,
             visibility: visibility as RawVisibility,
             arguments: Default::default(),
+            return_type: None,
         }
     }
 
+    pub(crate) fn new_typed<H, Args>(
+        name: impl AsRef<str>,
+        handler: H,
+        visibility: Visibility,
+    ) -> Self
+    where
+        H: TypedMethodHandler<Args>,
+        Args: 'static,
+    {
+        // The constructor and destructor can't declare return type.
+        let can_declare_return_type = !["__construct", "__destruct"]
+            .iter()
+            .any(|magic| name.as_ref().eq_ignore_ascii_case(magic));
+        let mut entity = Self::new(name, Some(Rc::new(TypedMethod::new(handler))), visibility);
+        entity.arguments = H::arguments();
+        if can_declare_return_type {
+            entity.return_type = H::return_type();
+        }
+        entity
+    }
+
+    pub(crate) fn new_typed_constructor<H, Args>(handler: H) -> Self
+    where
+        H: TypedConstructorHandler<Args>,
+        Args: 'static,
+    {
+        let mut entity = Self::new(
+            "__construct",
+            Some(Rc::new(TypedConstructor::new(handler))),
+            Visibility::Public,
+        );
+        entity.arguments = H::arguments();
+        entity
+    }
+
+    pub(crate) fn new_typed_static<H, Args>(
+        name: impl AsRef<str>,
+        handler: H,
+        visibility: Visibility,
+    ) -> Self
+    where
+        H: TypedFunctionHandler<Args>,
+        Args: 'static,
+    {
+        let mut entity = Self::new(name, Some(Rc::new(TypedFunction::new(handler))), visibility);
+        entity.arguments = H::arguments();
+        entity.return_type = H::return_type();
+        entity.set_vis_static();
+        entity
+    }
+
     #[inline]
     pub(crate) fn set_vis_static(&mut self) -> &mut Self {
         self.visibility |= ZEND_ACC_STATIC;
@@ -234,6 +934,14 @@ impl MethodEntity {
         self
     }
 
+    /// Declare the method as `final`, which can't be overridden by the
+    /// subclasses.
+    #[inline]
+    pub fn set_final(&mut self) -> &mut Self {
+        self.visibility |= ZEND_ACC_FINAL;
+        self
+    }
+
     /// Add single method argument info.
     #[inline]
     pub fn argument(&mut self, argument: Argument) -> &mut Self {
@@ -247,6 +955,29 @@ impl MethodEntity {
         self.arguments.extend(arguments);
         self
     }
+
+    /// Rename the arguments in order, see [`FunctionEntity::argument_names`].
+    pub fn argument_names<S: AsRef<str>>(
+        &mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> &mut Self {
+        rename_arguments(&mut self.arguments, names);
+        self
+    }
+
+    /// Declare the return type of method, see
+    /// [`FunctionEntity::return_type`].
+    #[inline]
+    pub fn return_type(&mut self, return_type: impl Into<TypeHint>) -> &mut Self {
+        self.return_type = Some(return_type.into());
+        self
+    }
+}
+
+fn rename_arguments<S: AsRef<str>>(arguments: &mut [Argument], names: impl IntoIterator<Item = S>) {
+    for (argument, name) in arguments.iter_mut().zip(names) {
+        argument.name = ensure_end_with_zero(name);
+    }
 }
 
 /// Function or method argument info.
@@ -254,47 +985,106 @@ pub struct Argument {
     name: CString,
     pass_by_ref: bool,
     required: bool,
+    is_variadic: bool,
+    type_hint: Option<TypeHint>,
+    default_value: Option<CString>,
 }
 
 impl Argument {
-    /// Indicate the argument is pass by value.
-    pub fn by_val(name: impl AsRef<str>) -> Self {
-        let name = ensure_end_with_zero(name);
+    fn new(name: impl AsRef<str>, pass_by_ref: bool, required: bool, is_variadic: bool) -> Self {
         Self {
-            name,
-            pass_by_ref: false,
-            required: true,
+            name: ensure_end_with_zero(name),
+            pass_by_ref,
+            required,
+            is_variadic,
+            type_hint: None,
+            default_value: None,
         }
     }
 
+    /// Indicate the argument is pass by value.
+    pub fn by_val(name: impl AsRef<str>) -> Self {
+        Self::new(name, false, true, false)
+    }
+
     /// Indicate the argument is pass by reference.
     pub fn by_ref(name: impl AsRef<str>) -> Self {
-        let name = ensure_end_with_zero(name);
-        Self {
-            name,
-            pass_by_ref: true,
-            required: true,
-        }
+        Self::new(name, true, true, false)
     }
 
     /// Indicate the argument is pass by value and is optional.
     pub fn by_val_optional(name: impl AsRef<str>) -> Self {
-        let name = ensure_end_with_zero(name);
-        Self {
-            name,
-            pass_by_ref: false,
-            required: false,
-        }
+        Self::new(name, false, false, false)
     }
 
     /// Indicate the argument is pass by reference nad is optional.
     pub fn by_ref_optional(name: impl AsRef<str>) -> Self {
-        let name = ensure_end_with_zero(name);
-        Self {
-            name,
-            pass_by_ref: true,
-            required: false,
-        }
+        Self::new(name, true, false, false)
+    }
+
+    /// Indicate the argument is variadic and pass by value, as `...$args`,
+    /// should be the last argument.
+    ///
+    /// The function accepts the extra named arguments too, see
+    /// [`Module::add_variadic_function`](crate::modules::Module::add_variadic_function).
+    pub fn variadic(name: impl AsRef<str>) -> Self {
+        Self::new(name, false, false, true)
+    }
+
+    /// Indicate the argument is variadic and pass by reference, as
+    /// `&...$args`, should be the last argument.
+    pub fn variadic_by_ref(name: impl AsRef<str>) -> Self {
+        Self::new(name, true, false, true)
+    }
+
+    /// Declare the type of argument, the engine will check and coerce the
+    /// passed value according to `strict_types` before calling the handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use phper::{
+    ///     functions::Argument,
+    ///     types::{BuiltinType, TypeHint},
+    /// };
+    ///
+    /// // int $a
+    /// let _ = Argument::by_val("a").with_type(BuiltinType::Int);
+    /// // ?Foo $b
+    /// let _ = Argument::by_val("b").with_type(TypeHint::class("Foo").nullable());
+    /// ```
+    pub fn with_type(mut self, type_hint: impl Into<TypeHint>) -> Self {
+        self.type_hint = Some(type_hint.into());
+        self
+    }
+
+    /// Declare the default value of argument, and make the argument optional.
+    ///
+    /// The default value is a PHP constant expression, such as a literal
+    /// (`"1"`, `"'foo'"`, `"[]"`), a constant name (`"PHP_INT_MAX"`) or
+    /// `"null"`. When the argument is omitted by the caller, the evaluated
+    /// default value will be filled into the arguments of handler, and it is
+    /// also shown by Reflection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use phper::functions::Argument;
+    ///
+    /// // $a = 100
+    /// let _ = Argument::by_val("a").with_default_value("100");
+    /// // $b = PHP_EOL
+    /// let _ = Argument::by_val("b").with_default_value("PHP_EOL");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panic if default value contains '\0'.
+    pub fn with_default_value(mut self, default_value: impl Into<String>) -> Self {
+        self.required = false;
+        self.default_value =
+            Some(CString::new(default_value.into()).expect("default value contains '\0'"));
+        self
     }
 }
 
@@ -391,26 +1181,13 @@ impl ZFunc {
     }
 }
 
-/// Just for type transmutation.
-pub(crate) union CallableTranslator {
-    pub(crate) callable: *const dyn Callable,
-    pub(crate) internal_arg_info: zend_internal_arg_info,
-    pub(crate) arg_info: zend_arg_info,
-}
-
 /// The entry for all registered PHP functions.
 unsafe extern "C" fn invoke(execute_data: *mut zend_execute_data, return_value: *mut zval) {
     let execute_data = ExecuteData::from_mut_ptr(execute_data);
     let return_value = ZVal::from_mut_ptr(return_value);
 
-    let num_args = execute_data.common_num_args();
-    let arg_info = execute_data.common_arg_info();
-
-    let last_arg_info = arg_info.offset((num_args + 1) as isize);
-    let translator = CallableTranslator {
-        arg_info: *last_arg_info,
-    };
-    let handler = translator.callable;
+    let return_arg_info = execute_data.common_arg_info().offset(-1);
+    let handler = (*return_arg_info).default_value as *const Rc<dyn Callable>;
     let handler = handler.as_ref().expect("handler is null");
 
     // Check arguments count.
@@ -429,14 +1206,73 @@ unsafe extern "C" fn invoke(execute_data: *mut zend_execute_data, return_value:
         return;
     }
 
+    // Check arguments types, the type error is thrown by the engine.
+    if !phper_zend_verify_arg_types(execute_data.as_mut_ptr()) {
+        *return_value = ().into();
+        return;
+    }
+
+    // The object whose state isn't built yet can only call the constructor, or
+    // `__unserialize` which may build the state too.
+    let func = execute_data.func().as_ptr();
+    let scope = (*func).common.scope;
+    if let Some(this) = execute_data.get_this_mut() {
+        let can_build_state = !scope.is_null()
+            && ((*scope).constructor == func.cast_mut()
+                || (*scope).__unserialize == func.cast_mut());
+        if is_uninitialized(this) && !can_build_state {
+            let class_name = this.get_class().get_name().to_str().unwrap_or_default();
+            throw(NotInitializedError::new(class_name.to_owned()));
+            *return_value = ().into();
+            return;
+        }
+    }
+
     let mut arguments = execute_data.get_parameters_array();
-    let arguments = arguments.as_mut_slice();
 
-    handler.call(execute_data, transmute(arguments), return_value);
+    // Fill the omitted optional arguments with the default values.
+    let num_passed_args = arguments.len();
+    let arg_infos = execute_data.common_arg_info();
+    for i in num_passed_args..execute_data.common_num_args() as usize {
+        let mut default_value = ZVal::default();
+        if !phper_zend_get_default_from_internal_arg_info(
+            default_value.as_mut_ptr(),
+            arg_infos.add(i).cast_const().cast(),
+        ) {
+            if !eg!(exception).is_null() {
+                *return_value = ().into();
+                return;
+            }
+            break;
+        }
+        arguments.push(ManuallyDrop::new(default_value));
+    }
+
+    handler.call(
+        execute_data,
+        transmute(arguments.as_mut_slice()),
+        return_value,
+    );
+
+    // The default values are owned here, unlike the passed arguments.
+    for default_value in &mut arguments[num_passed_args..] {
+        ManuallyDrop::drop(default_value);
+    }
+
+    // The return value of internal function isn't checked by the engine, so check
+    // it like the engine does in debug mode, to find the mismatch between handler
+    // and declaration.
+    #[cfg(phper_debug)]
+    if !phper_zend_verify_return_type(execute_data.as_mut_ptr(), return_value.as_mut_ptr()) {
+        *return_value = ().into();
+    }
 }
 
 /// Call user function by name.
 ///
+/// The arguments passed to the by-reference parameters are written back after
+/// calling.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -450,16 +1286,54 @@ unsafe extern "C" fn invoke(execute_data: *mut zend_execute_data, return_value:
 ///     assert_eq!(ret.expect_z_str()?.to_str(), Ok(r#"{"a":1,"b":2}"#));
 ///     Ok(())
 /// }
+///
+/// fn preg_match() -> phper::Result<()> {
+///     let mut arguments = [ZVal::from("/(\\d+)/"), ZVal::from("abc123"), ZVal::default()];
+///     let ret = call("preg_match", &mut arguments)?;
+///     assert_eq!(ret.expect_long()?, 1);
+///     let matches = arguments[2].expect_z_arr()?;
+///     assert_eq!(matches.get(1u64).unwrap().expect_z_str()?.to_str(), Ok("123"));
+///     Ok(())
+/// }
 /// ```
 pub fn call(callable: impl Into<ZVal>, arguments: impl AsMut<[ZVal]>) -> crate::Result<ZVal> {
     let mut func = callable.into();
-    call_internal(&mut func, None, arguments)
+    call_internal(&mut func, None, arguments, None)
+}
+
+/// Call user function by name, with the named arguments, like
+/// `str_pad(string: "a", length: 3)` in PHP.
+///
+/// The by-reference parameters should be passed by the positional arguments,
+/// to get the written values back.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{arrays::ZArray, functions::call_with_named, values::ZVal};
+///
+/// fn str_pad() -> phper::Result<()> {
+///     let mut named = ZArray::new();
+///     named.insert("pad_type", ZVal::from(0));
+///     let ret = call_with_named("str_pad", [ZVal::from("a"), ZVal::from(3)], &named)?;
+///     assert_eq!(ret.expect_z_str()?.to_str(), Ok("  a"));
+///     Ok(())
+/// }
+/// ```
+pub fn call_with_named(
+    callable: impl Into<ZVal>,
+    arguments: impl AsMut<[ZVal]>,
+    named_arguments: &ZArr,
+) -> crate::Result<ZVal> {
+    let mut func = callable.into();
+    call_internal(&mut func, None, arguments, Some(named_arguments))
 }
 
 pub(crate) fn call_internal(
     func: &mut ZVal,
     mut object: Option<&mut ZObj>,
     mut arguments: impl AsMut<[ZVal]>,
+    named_arguments: Option<&ZArr>,
 ) -> crate::Result<ZVal> {
     let func_ptr = func.as_mut_ptr();
     let arguments = arguments.as_mut();
@@ -476,11 +1350,18 @@ pub(crate) fn call_internal(
             ret.as_mut_ptr(),
             arguments.as_mut_ptr().cast(),
             arguments.len() as u32,
-            null_mut(),
+            named_arguments_ptr(named_arguments),
         );
     })
 }
 
+/// The named arguments aren't modified by the engine.
+fn named_arguments_ptr(named_arguments: Option<&ZArr>) -> *mut zend_array {
+    named_arguments
+        .map(|arr| arr.as_ptr().cast_mut())
+        .unwrap_or(null_mut())
+}
+
 /// call function with raw pointer.
 /// call_fn parameters: (return_value)
 pub(crate) fn call_raw_common(call_fn: impl FnOnce(&mut ZVal)) -> crate::Result<ZVal> {
@@ -506,3 +1387,122 @@ pub(crate) fn call_raw_common(call_fn: impl FnOnce(&mut ZVal)) -> crate::Result<
 
     Ok(ret)
 }
+
+/// The callable value with the resolved function cached, so the repeated
+/// calling skips resolving the callable again.
+///
+/// The callable value is held, so the `ZCallable` can be stored in the object
+/// state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{functions::ZCallable, values::ZVal};
+///
+/// fn sum_by(callback: &ZVal, items: &[i64]) -> phper::Result<i64> {
+///     let mut callable = ZCallable::new(callback.clone())?;
+///     let mut sum = 0;
+///     for item in items {
+///         sum += callable.call([ZVal::from(*item)])?.expect_long()?;
+///     }
+///     Ok(sum)
+/// }
+/// ```
+pub struct ZCallable {
+    callable: ZVal,
+    fcc: zend_fcall_info_cache,
+}
+
+impl ZCallable {
+    /// Resolve the callable value, like `is_callable` of PHP.
+    ///
+    /// # Errors
+    ///
+    /// Return [NotCallableError](crate::errors::NotCallableError) when the
+    /// value isn't a valid callback.
+    pub fn new(callable: impl Into<ZVal>) -> crate::Result<Self> {
+        let mut callable = callable.into();
+        unsafe {
+            let mut fcc = mem::zeroed::<zend_fcall_info_cache>();
+            let mut error = null_mut();
+            if phper_zend_is_callable_ex(callable.as_mut_ptr(), &mut fcc, &mut error) {
+                return Ok(Self { callable, fcc });
+            }
+            let reason = if error.is_null() {
+                "no array or string given".to_owned()
+            } else {
+                ZString::from_raw(error).to_str()?.to_owned()
+            };
+            Err(NotCallableError::new(reason).into())
+        }
+    }
+
+    /// Get the callable value.
+    #[inline]
+    pub fn as_z_val(&self) -> &ZVal {
+        &self.callable
+    }
+
+    /// Call the cached function, the arguments passed to the by-reference
+    /// parameters are written back after calling.
+    ///
+    /// # Errors
+    ///
+    /// Return Err when the called function throws exception.
+    #[inline]
+    pub fn call(&mut self, arguments: impl AsMut<[ZVal]>) -> crate::Result<ZVal> {
+        self.call_internal(arguments, None)
+    }
+
+    /// Call the cached function with the named arguments, see
+    /// [call_with_named].
+    ///
+    /// # Errors
+    ///
+    /// Return Err when the called function throws exception.
+    #[inline]
+    pub fn call_with_named(
+        &mut self,
+        arguments: impl AsMut<[ZVal]>,
+        named_arguments: &ZArr,
+    ) -> crate::Result<ZVal> {
+        self.call_internal(arguments, Some(named_arguments))
+    }
+
+    fn call_internal(
+        &mut self,
+        mut arguments: impl AsMut<[ZVal]>,
+        named_arguments: Option<&ZArr>,
+    ) -> crate::Result<ZVal> {
+        let arguments = arguments.as_mut();
+        call_raw_common(|ret| unsafe {
+            phper_zend_call_function_with_cache(
+                self.callable.as_mut_ptr(),
+                &mut self.fcc,
+                ret.as_mut_ptr(),
+                arguments.as_mut_ptr().cast(),
+                arguments.len() as u32,
+                named_arguments_ptr(named_arguments),
+            );
+        })
+    }
+}
+
+impl Clone for ZCallable {
+    fn clone(&self) -> Self {
+        // The cached function and object are owned by the callable value,
+        // which is shared by the cloned.
+        Self {
+            callable: self.callable.clone(),
+            fcc: self.fcc,
+        }
+    }
+}
+
+impl fmt::Debug for ZCallable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZCallable")
+            .field("callable", &self.callable)
+            .finish()
+    }
+}
@@ -19,6 +19,7 @@ mod macros;
 pub mod arrays;
 pub mod classes;
 pub mod constants;
+pub mod enums;
 pub mod errors;
 pub mod functions;
 pub mod ini;
@@ -12,11 +12,15 @@
 
 use crate::constants;
 use crate::{
+    arrays::ZArr,
     c_str_ptr,
     classes::{entity::ClassEntity, InterfaceEntity},
     constants::Constant,
+    enums::{EnumBackingType, EnumEntity},
     errors::Throwable,
-    functions::{Function, FunctionEntity, FunctionEntry},
+    functions::{
+        self, Function, FunctionEntity, FunctionEntry, TypedFunctionHandler, VariadicFunction,
+    },
     ini,
     sys::*,
     utils::ensure_end_with_zero,
@@ -61,6 +65,8 @@ unsafe extern "C" fn module_startup(_type: c_int, module_number: c_int) -> c_int
 
     ini::register(take(&mut module.ini_entities), module_number);
 
+    functions::startup_closures();
+
     for mut entity in take(&mut module.entities).into_iter() {
         if let Err(err) = entity.register(module_number) {
             crate::output::log(
@@ -87,6 +93,8 @@ unsafe extern "C" fn module_shutdown(_type: c_int, module_number: c_int) -> c_in
 
         ini::unregister(module_number);
 
+        functions::shutdown_closures();
+
         if let Some(f) = take(&mut module.module_shutdown) {
             f(ModuleInfo {
                 ty: _type,
@@ -167,6 +175,7 @@ pub(crate) enum Entities {
     Constant(Constant),
     Class(ClassEntity),
     Interface(InterfaceEntity),
+    Enum(Box<dyn Registerer>),
 }
 
 impl Registerer for Entities {
@@ -175,6 +184,7 @@ impl Registerer for Entities {
             Entities::Constant(con) => con.register(module_number),
             Entities::Class(class) => class.register(module_number),
             Entities::Interface(interface) => interface.register(module_number),
+            Entities::Enum(e) => e.register(module_number),
         }
     }
 }
@@ -233,6 +243,91 @@ impl Module {
         self.function_entities.last_mut().unwrap()
     }
 
+    /// Register variadic function to module.
+    ///
+    /// The handler receives the declared arguments, the values collected by
+    /// the variadic argument, and the extra named arguments. The variadic
+    /// argument should be declared by
+    /// [`Argument::variadic`](crate::functions::Argument::variadic).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{functions::Argument, modules::Module, values::ZVal};
+    ///
+    /// let mut module = Module::new("test", "0.1.0", "");
+    /// module
+    ///     .add_variadic_function("count_args", |_, values, named| {
+    ///         Ok::<_, phper::Error>((values.len() + named.iter().count()) as i64)
+    ///     })
+    ///     .argument(Argument::variadic("args"));
+    /// ```
+    pub fn add_variadic_function<F, Z, E>(
+        &mut self,
+        name: impl AsRef<str>,
+        handler: F,
+    ) -> &mut FunctionEntity
+    where
+        F: Fn(&mut [ZVal], &mut [ZVal], &ZArr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.function_entities.push(FunctionEntity::new(
+            name,
+            Rc::new(VariadicFunction::new(handler)),
+        ));
+        self.function_entities.last_mut().unwrap()
+    }
+
+    /// Register function with typed arguments to module.
+    ///
+    /// The arguments are extracted by [`FromZVal`](crate::values::FromZVal),
+    /// and the returned value is converted by
+    /// [`IntoZVal`](crate::values::IntoZVal). The argument infos and the
+    /// return type are inferred from the signature of handler, the arguments
+    /// are named `arg1`, `arg2`, etc., which can be renamed by
+    /// [`FunctionEntity::argument_names`]. The trailing `Option` arguments are
+    /// optional and default to `null`.
+    ///
+    /// If an argument fails to be extracted, the `TypeError` will be thrown
+    /// with the position and name of argument.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{arrays::ZArr, modules::Module};
+    ///
+    /// let mut module = Module::new("test", "0.1.0", "");
+    /// module
+    ///     .add_typed_function(
+    ///         "repeat",
+    ///         |s: &str, times: i64, _: Option<&ZArr>| -> phper::Result<String> {
+    ///             Ok(s.repeat(times as usize))
+    ///         },
+    ///     )
+    ///     .argument_names(["s", "times", "options"]);
+    /// ```
+    pub fn add_typed_function<H, Args>(
+        &mut self,
+        name: impl AsRef<str>,
+        handler: H,
+    ) -> &mut FunctionEntity
+    where
+        H: TypedFunctionHandler<Args>,
+        Args: 'static,
+    {
+        self.function_entities
+            .push(FunctionEntity::new_typed(name, handler));
+        self.function_entities.last_mut().unwrap()
+    }
+
+    /// Register the function entity to module, usually generated by
+    /// [`php_function`](crate::php_function).
+    pub fn add_function_entity(&mut self, entity: FunctionEntity) -> &mut FunctionEntity {
+        self.function_entities.push(entity);
+        self.function_entities.last_mut().unwrap()
+    }
+
     /// Register class to module.
     pub fn add_class(&mut self, class: ClassEntity) {
         self.entities.push(Entities::Class(class));
@@ -243,6 +338,11 @@ impl Module {
         self.entities.push(Entities::Interface(interface));
     }
 
+    /// Register enum to module, since PHP 8.1.
+    pub fn add_enum<B: EnumBackingType>(&mut self, e: EnumEntity<B>) {
+        self.entities.push(Entities::Enum(Box::new(e)));
+    }
+
     /// Register constant to module.
     pub fn add_constant(
         &mut self,
@@ -11,14 +11,18 @@
 //! Apis relate to [zend_object].
 
 use crate::{
+    arrays::ZArr,
     classes::entry::ClassEntry,
+    errors::{NotInitializedError, StateTypeError},
     functions::{call_internal, call_raw_common, ZFunc},
+    strings::ZStr,
     sys::*,
     values::ZVal,
 };
+use memoffset::offset_of;
 use phper_alloc::{RefClone, ToRefOwned};
 use std::{
-    any::Any,
+    any::{type_name, Any},
     borrow::Borrow,
     convert::TryInto,
     ffi::c_void,
@@ -28,7 +32,6 @@ use std::{
     ops::{Deref, DerefMut},
     ptr::null_mut,
 };
-use memoffset::offset_of;
 
 /// Wrapper of [zend_object].
 #[repr(transparent)]
@@ -237,7 +240,44 @@ impl ZObj {
         arguments: impl AsMut<[ZVal]>,
     ) -> crate::Result<ZVal> {
         let mut method = method_name.into();
-        call_internal(&mut method, Some(self), arguments)
+        call_internal(&mut method, Some(self), arguments, None)
+    }
+
+    /// Call the method of object with the named arguments, see
+    /// [call_with_named](crate::functions::call_with_named).
+    pub fn call_with_named(
+        &mut self,
+        method_name: &str,
+        arguments: impl AsMut<[ZVal]>,
+        named_arguments: &ZArr,
+    ) -> crate::Result<ZVal> {
+        let mut method = method_name.into();
+        call_internal(&mut method, Some(self), arguments, Some(named_arguments))
+    }
+
+    /// Get the case name if the object is an enum case, otherwise returns
+    /// `None`.
+    pub fn get_enum_case_name(&self) -> Option<&ZStr> {
+        unsafe {
+            ZVal::try_from_ptr(phper_enum_fetch_case_name(self.as_ptr())).and_then(ZVal::as_z_str)
+        }
+    }
+
+    /// Get the backing value if the object is a case of backed enum, otherwise
+    /// returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::classes::ClassEntry;
+    ///
+    /// fn example() -> phper::Result<i64> {
+    ///     let case = ClassEntry::from_globals("Status")?.get_enum_case("Active")?;
+    ///     case.get_enum_backing_value().unwrap().expect_long()
+    /// }
+    /// ```
+    pub fn get_enum_backing_value(&self) -> Option<&ZVal> {
+        unsafe { ZVal::try_from_ptr(phper_enum_fetch_case_value(self.as_ptr())) }
     }
 
     pub(crate) fn call_construct(&mut self, arguments: impl AsMut<[ZVal]>) -> crate::Result<()> {
@@ -381,6 +421,11 @@ impl Debug for ZObject {
 
 pub(crate) type AnyState = *mut dyn Any;
 
+/// The placeholder state of the object, whose state is built by the
+/// constructor, see
+/// [ClassEntity::new_with_constructor](crate::classes::entity::ClassEntity::new_with_constructor).
+pub(crate) struct UninitState;
+
 /// The object owned state, usually as the parameter of method handler.
 #[repr(C)]
 pub struct StateObj {
@@ -418,6 +463,18 @@ impl StateObj {
         drop(Box::from_raw(self.any_state));
     }
 
+    /// Whether the state is built, only the object whose state is built by the
+    /// constructor can be uninitialized.
+    pub(crate) fn is_initialized(&self) -> bool {
+        unsafe { !(*self.any_state).is::<UninitState>() }
+    }
+
+    /// Drops the current state and stores the new one.
+    pub(crate) fn replace_state(&mut self, state: Box<dyn Any>) {
+        unsafe { self.drop_state() };
+        self.any_state = Box::into_raw(state);
+    }
+
     #[inline]
     pub(crate) fn as_mut_any_state(&mut self) -> &mut AnyState {
         &mut self.any_state
@@ -438,6 +495,10 @@ impl StateObj {
 
 impl StateObj {
     /// Gets inner state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state isn't `T`, see [StateObj::try_as_state].
     pub fn as_state<T: 'static>(&self) -> &T {
         unsafe {
             let any_state = self.any_state.as_ref().unwrap();
@@ -446,12 +507,47 @@ impl StateObj {
     }
 
     /// Gets inner mutable state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state isn't `T`, see [StateObj::try_as_mut_state].
     pub fn as_mut_state<T: 'static>(&mut self) -> &mut T {
         unsafe {
             let any_state = self.any_state.as_mut().unwrap();
             any_state.downcast_mut().unwrap()
         }
     }
+
+    /// Gets inner state, fails if the object isn't initialized, or the state
+    /// isn't `T`.
+    pub fn try_as_state<T: 'static>(&self) -> crate::Result<&T> {
+        self.check_state::<T>()?;
+        Ok(self.as_state())
+    }
+
+    /// Gets inner mutable state, fails if the object isn't initialized, or the
+    /// state isn't `T`.
+    pub fn try_as_mut_state<T: 'static>(&mut self) -> crate::Result<&mut T> {
+        self.check_state::<T>()?;
+        Ok(self.as_mut_state())
+    }
+
+    fn check_state<T: 'static>(&self) -> crate::Result<()> {
+        if unsafe { (*self.any_state).is::<T>() } {
+            return Ok(());
+        }
+        let class_name = self
+            .object
+            .get_class()
+            .get_name()
+            .to_str()
+            .unwrap_or_default()
+            .to_owned();
+        if !self.is_initialized() {
+            return Err(NotInitializedError::new(class_name).into());
+        }
+        Err(StateTypeError::new(class_name, type_name::<T>()).into())
+    }
 }
 
 impl Deref for StateObj {
@@ -13,7 +13,7 @@
 use crate::{c_str, sys::*};
 use derive_more::From;
 use std::{
-    ffi::CStr,
+    ffi::{CStr, CString},
     fmt::{self, Debug, Display},
     os::raw::c_int,
 };
@@ -211,3 +211,188 @@ impl From<&[u8]> for Scalar {
         Self::Bytes(b.to_owned())
     }
 }
+
+/// Builtin PHP types, used in type declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuiltinType {
+    /// `mixed`.
+    Mixed,
+    /// `null`.
+    Null,
+    /// `bool`.
+    Bool,
+    /// `false`.
+    False,
+    /// `true`.
+    True,
+    /// `int`.
+    Int,
+    /// `float`.
+    Float,
+    /// `string`.
+    String,
+    /// `array`.
+    Array,
+    /// `object`.
+    Object,
+    /// `callable`.
+    Callable,
+    /// `iterable`.
+    Iterable,
+    /// `void`, only for return type.
+    Void,
+    /// `never`, only for return type.
+    Never,
+    /// `static`, only for return type.
+    Static,
+}
+
+impl BuiltinType {
+    const fn code(self) -> u32 {
+        match self {
+            BuiltinType::Mixed => IS_MIXED,
+            BuiltinType::Null => IS_NULL,
+            BuiltinType::Bool => _IS_BOOL,
+            BuiltinType::False => IS_FALSE,
+            BuiltinType::True => IS_TRUE,
+            BuiltinType::Int => IS_LONG,
+            BuiltinType::Float => IS_DOUBLE,
+            BuiltinType::String => IS_STRING,
+            BuiltinType::Array => IS_ARRAY,
+            BuiltinType::Object => IS_OBJECT,
+            BuiltinType::Callable => IS_CALLABLE,
+            BuiltinType::Iterable => IS_ITERABLE,
+            BuiltinType::Void => IS_VOID,
+            BuiltinType::Never => IS_NEVER,
+            BuiltinType::Static => IS_STATIC,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            BuiltinType::Mixed => "mixed",
+            BuiltinType::Null => "null",
+            BuiltinType::Bool => "bool",
+            BuiltinType::False => "false",
+            BuiltinType::True => "true",
+            BuiltinType::Int => "int",
+            BuiltinType::Float => "float",
+            BuiltinType::String => "string",
+            BuiltinType::Array => "array",
+            BuiltinType::Object => "object",
+            BuiltinType::Callable => "callable",
+            BuiltinType::Iterable => "iterable",
+            BuiltinType::Void => "void",
+            BuiltinType::Never => "never",
+            BuiltinType::Static => "static",
+        }
+    }
+}
+
+/// Type declaration of function argument, return value or property.
+///
+/// # Examples
+///
+/// ```
+/// use phper::types::{BuiltinType, TypeHint};
+///
+/// // ?int
+/// let _ = TypeHint::from(BuiltinType::Int).nullable();
+/// // Foo|string
+/// let _ = TypeHint::class("Foo").or(BuiltinType::String);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeHint {
+    builtins: Vec<BuiltinType>,
+    class_names: Vec<String>,
+}
+
+impl TypeHint {
+    /// Builtin type declaration.
+    pub fn builtin(ty: BuiltinType) -> Self {
+        Self {
+            builtins: vec![ty],
+            class_names: Vec::new(),
+        }
+    }
+
+    /// Class or interface type declaration.
+    pub fn class(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            builtins: Vec::new(),
+            class_names: vec![name.trim_start_matches('\\').to_owned()],
+        }
+    }
+
+    /// Make the type nullable, as `?int`.
+    pub fn nullable(self) -> Self {
+        self.or(BuiltinType::Null)
+    }
+
+    /// Union with other type, as `int|string`.
+    pub fn or(mut self, other: impl Into<TypeHint>) -> Self {
+        let other = other.into();
+        for ty in other.builtins {
+            if !self.builtins.contains(&ty) {
+                self.builtins.push(ty);
+            }
+        }
+        for name in other.class_names {
+            if !self.class_names.contains(&name) {
+                self.class_names.push(name);
+            }
+        }
+        self
+    }
+
+    /// Detects if the type accepts null.
+    pub fn is_nullable(&self) -> bool {
+        self.builtins
+            .iter()
+            .any(|ty| matches!(ty, BuiltinType::Null | BuiltinType::Mixed))
+    }
+
+    /// Mask of builtin types, as `MAY_BE_*` flags.
+    pub(crate) fn type_mask(&self) -> u32 {
+        self.builtins.iter().fold(0, |mask, ty| {
+            mask | unsafe { phper_zend_type_mask_from_code(ty.code()) }
+        })
+    }
+
+    /// Class names joined by `|`, will be split by the engine when registering.
+    pub(crate) fn class_name(&self) -> Option<CString> {
+        if self.class_names.is_empty() {
+            return None;
+        }
+        Some(CString::new(self.class_names.join("|")).expect("class name contains '\\0'"))
+    }
+}
+
+impl Display for TypeHint {
+    /// Formats as the type declaration in PHP, such as `?int` or
+    /// `Foo|string`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self
+            .class_names
+            .iter()
+            .map(String::as_str)
+            .chain(self.builtins.iter().map(|ty| ty.name()))
+            .filter(|name| *name != "null")
+            .collect::<Vec<_>>();
+        if self.builtins.contains(&BuiltinType::Null) {
+            if names.len() == 1 {
+                return write!(f, "?{}", names[0]);
+            }
+            names.push("null");
+        }
+        Display::fmt(&names.join("|"), f)
+    }
+}
+
+impl From<BuiltinType> for TypeHint {
+    fn from(ty: BuiltinType) -> Self {
+        Self::builtin(ty)
+    }
+}
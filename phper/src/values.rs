@@ -13,16 +13,19 @@
 use crate::{
     alloc::EBox,
     arrays::{ZArr, ZArray},
-    errors::ExpectTypeError,
-    functions::{call_internal, ZFunc},
+    errors::{ExpectTypeError, Throwable},
+    functions::{
+        call_internal, new_closure, Argument, Function, TypedFunction, TypedFunctionHandler,
+        ZCallable, ZFunc,
+    },
     objects::{StateObject, ZObj, ZObject},
     references::ZRef,
     resources::ZRes,
     strings::{ZStr, ZString},
     sys::*,
-    types::TypeInfo,
+    types::{BuiltinType, TypeHint, TypeInfo},
 };
-use phper_alloc::RefClone;
+use phper_alloc::{RefClone, ToRefOwned};
 use std::{
     convert::TryInto,
     ffi::CStr,
@@ -30,6 +33,7 @@ use std::{
     fmt::Debug,
     marker::PhantomData,
     mem::{transmute, zeroed, ManuallyDrop, MaybeUninit},
+    rc::Rc,
     str,
 };
 
@@ -127,6 +131,12 @@ impl ExecuteData {
         unsafe { phper_zend_num_args(self.as_ptr()).try_into().unwrap() }
     }
 
+    /// Gets the extra named arguments collected by the variadic argument, if
+    /// exists.
+    pub fn extra_named_params(&self) -> Option<&ZArr> {
+        unsafe { ZArr::try_from_ptr(phper_zend_call_extra_named_params(self.as_ptr())) }
+    }
+
     /// Gets associated function.
     pub fn func(&self) -> &ZFunc {
         unsafe { ZFunc::from_mut_ptr(self.inner.func) }
@@ -575,12 +585,74 @@ impl ZVal {
 
     /// Call only when self is a callable (string or array or closure).
     ///
+    /// The arguments passed to the by-reference parameters are written back
+    /// after calling.
+    ///
     /// # Errors
     ///
     /// Return Err when self is not callable, or called failed.
     #[inline]
     pub fn call(&mut self, arguments: impl AsMut<[ZVal]>) -> crate::Result<ZVal> {
-        call_internal(self, None, arguments)
+        call_internal(self, None, arguments, None)
+    }
+
+    /// Call only when self is a callable, with the named arguments, see
+    /// [call_with_named](crate::functions::call_with_named).
+    ///
+    /// # Errors
+    ///
+    /// Return Err when self is not callable, or called failed.
+    #[inline]
+    pub fn call_with_named(
+        &mut self,
+        arguments: impl AsMut<[ZVal]>,
+        named_arguments: &ZArr,
+    ) -> crate::Result<ZVal> {
+        call_internal(self, None, arguments, Some(named_arguments))
+    }
+
+    /// Creates a php `Closure` object from the rust closure, the declared
+    /// arguments are visible to `Reflection`, and the captured data is
+    /// dropped when the closure object is freed.
+    ///
+    /// The closures copied by cloning, `Closure::bind` or `Closure::bindTo`
+    /// share the captured data, which is dropped when the last of them is
+    /// freed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{functions::Argument, values::ZVal};
+    ///
+    /// let prefix = String::from("Hello, ");
+    /// let closure = ZVal::from_closure([Argument::by_val("name")], move |arguments| {
+    ///     let name = arguments[0].expect_z_str()?.to_str()?;
+    ///     Ok::<_, phper::Error>(format!("{}{}", prefix, name))
+    /// });
+    /// ```
+    pub fn from_closure<F, Z, E>(arguments: impl IntoIterator<Item = Argument>, handler: F) -> Self
+    where
+        F: Fn(&mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        let arguments = arguments.into_iter().collect::<Vec<_>>();
+        new_closure(Rc::new(Function::new(handler)), &arguments, None)
+    }
+
+    /// Creates a php `Closure` object from the typed handler, the argument
+    /// and return types are inferred as
+    /// [`FunctionEntity::new_typed`](crate::functions::FunctionEntity::new_typed).
+    pub fn from_typed_closure<H, Args>(handler: H) -> Self
+    where
+        H: TypedFunctionHandler<Args>,
+        Args: 'static,
+    {
+        new_closure(
+            Rc::new(TypedFunction::new(handler)),
+            &H::arguments(),
+            H::return_type().as_ref(),
+        )
     }
 }
 
@@ -810,3 +882,333 @@ impl<T: Into<ZVal>> From<EBox<T>> for ZVal {
         t.into_inner().into()
     }
 }
+
+/// Extracts the argument of typed handler from [`ZVal`].
+///
+/// The declared type of argument is inferred by [`FromZVal::type_hint`], so
+/// the engine has checked and coerced the passed value before extracting.
+///
+/// The borrowed arguments live as long as the calling, see [`TypedArgument`].
+pub trait FromZVal<'a>: Sized {
+    /// The type declaration of argument, `None` means untyped (`mixed`).
+    fn type_hint() -> Option<TypeHint>;
+
+    /// Whether the argument can be omitted, only the trailing optional
+    /// arguments are optional, and default to `null`.
+    fn is_optional() -> bool {
+        false
+    }
+
+    /// Extracts from the argument value.
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self>;
+}
+
+impl<'a> FromZVal<'a> for bool {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Bool.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_bool()
+    }
+}
+
+impl<'a> FromZVal<'a> for i64 {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Int.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_long()
+    }
+}
+
+impl<'a> FromZVal<'a> for f64 {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Float.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_double()
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a str {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::String.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.expect_z_str()?.to_str()?)
+    }
+}
+
+impl<'a> FromZVal<'a> for String {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::String.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.expect_z_str()?.to_str()?.to_owned())
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a ZStr {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::String.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_z_str()
+    }
+}
+
+impl<'a> FromZVal<'a> for ZString {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::String.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.expect_z_str()?.to_owned())
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a ZArr {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Array.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_z_arr()
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a mut ZArr {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Array.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_mut_z_arr()
+    }
+}
+
+impl<'a> FromZVal<'a> for ZArray {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Array.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.expect_z_arr()?.to_owned())
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a ZObj {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Object.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_z_obj()
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a mut ZObj {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Object.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        val.expect_mut_z_obj()
+    }
+}
+
+impl<'a> FromZVal<'a> for ZObject {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Object.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.expect_mut_z_obj()?.to_ref_owned())
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a ZVal {
+    fn type_hint() -> Option<TypeHint> {
+        None
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val)
+    }
+}
+
+impl<'a> FromZVal<'a> for &'a mut ZVal {
+    fn type_hint() -> Option<TypeHint> {
+        None
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val)
+    }
+}
+
+impl<'a> FromZVal<'a> for ZVal {
+    fn type_hint() -> Option<TypeHint> {
+        None
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        Ok(val.clone())
+    }
+}
+
+impl<'a> FromZVal<'a> for ZCallable {
+    fn type_hint() -> Option<TypeHint> {
+        Some(BuiltinType::Callable.into())
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        ZCallable::new(val.clone())
+    }
+}
+
+impl<'a, T: FromZVal<'a>> FromZVal<'a> for Option<T> {
+    fn type_hint() -> Option<TypeHint> {
+        T::type_hint().map(TypeHint::nullable)
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+
+    fn from_z_val(val: &'a mut ZVal) -> crate::Result<Self> {
+        if val.get_type_info().is_null() {
+            Ok(None)
+        } else {
+            T::from_z_val(val).map(Some)
+        }
+    }
+}
+
+/// Rebinds the lifetime of argument type of typed handler.
+///
+/// The handler is required to accept [`TypedArgument::Borrowed`] with any
+/// lifetime, so the borrowed arguments such as `&str` can't escape from the
+/// calling, after which the argument values are released.
+pub trait TypedArgument {
+    /// The argument type borrowing the argument value for `'a`.
+    type Borrowed<'a>: FromZVal<'a>;
+}
+
+macro_rules! impl_owned_typed_argument {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl TypedArgument for $ty {
+                type Borrowed<'a> = $ty;
+            }
+        )*
+    };
+}
+
+impl_owned_typed_argument!(bool, i64, f64, String, ZString, ZArray, ZObject, ZVal, ZCallable);
+
+impl TypedArgument for &str {
+    type Borrowed<'a> = &'a str;
+}
+
+impl TypedArgument for &ZStr {
+    type Borrowed<'a> = &'a ZStr;
+}
+
+impl TypedArgument for &ZArr {
+    type Borrowed<'a> = &'a ZArr;
+}
+
+impl TypedArgument for &mut ZArr {
+    type Borrowed<'a> = &'a mut ZArr;
+}
+
+impl TypedArgument for &ZObj {
+    type Borrowed<'a> = &'a ZObj;
+}
+
+impl TypedArgument for &mut ZObj {
+    type Borrowed<'a> = &'a mut ZObj;
+}
+
+impl TypedArgument for &ZVal {
+    type Borrowed<'a> = &'a ZVal;
+}
+
+impl TypedArgument for &mut ZVal {
+    type Borrowed<'a> = &'a mut ZVal;
+}
+
+impl<T: TypedArgument> TypedArgument for Option<T> {
+    type Borrowed<'a> = Option<T::Borrowed<'a>>;
+}
+
+/// Converts the returned value of typed handler into [`ZVal`].
+///
+/// The return type is inferred by [`IntoZVal::type_hint`].
+pub trait IntoZVal {
+    /// The return type declaration, `None` means untyped (`mixed`).
+    fn type_hint() -> Option<TypeHint>;
+
+    /// Converts into the returned value.
+    fn into_z_val(self) -> ZVal;
+}
+
+macro_rules! impl_into_z_val {
+    ($($ty:ty => $builtin:expr),* $(,)?) => {
+        $(
+            impl IntoZVal for $ty {
+                fn type_hint() -> Option<TypeHint> {
+                    Some($builtin.into())
+                }
+
+                fn into_z_val(self) -> ZVal {
+                    ZVal::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_z_val! {
+    () => BuiltinType::Void,
+    bool => BuiltinType::Bool,
+    i64 => BuiltinType::Int,
+    f64 => BuiltinType::Float,
+    &str => BuiltinType::String,
+    String => BuiltinType::String,
+    Vec<u8> => BuiltinType::String,
+    ZString => BuiltinType::String,
+    ZArray => BuiltinType::Array,
+    ZObject => BuiltinType::Object,
+    StateObject => BuiltinType::Object,
+}
+
+impl IntoZVal for ZVal {
+    fn type_hint() -> Option<TypeHint> {
+        None
+    }
+
+    fn into_z_val(self) -> ZVal {
+        self
+    }
+}
+
+impl<T: IntoZVal> IntoZVal for Option<T> {
+    fn type_hint() -> Option<TypeHint> {
+        T::type_hint().map(TypeHint::nullable)
+    }
+
+    fn into_z_val(self) -> ZVal {
+        match self {
+            Some(t) => t.into_z_val(),
+            None => ().into(),
+        }
+    }
+}
@@ -9,12 +9,20 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
-    alloc::ToRefOwned, arrays::ZArray, functions::Argument, modules::Module, objects::ZObject,
+    alloc::ToRefOwned,
+    arrays::{IterKey, ZArray},
+    functions::Argument,
+    modules::Module,
+    objects::ZObject,
+    types::{BuiltinType, TypeHint},
     values::ZVal,
 };
 
 pub fn integrate(module: &mut Module) {
     integrate_arguments(module);
+    integrate_typed_arguments(module);
+    integrate_default_arguments(module);
+    integrate_variadic_arguments(module);
 }
 
 fn integrate_arguments(module: &mut Module) {
@@ -96,3 +104,86 @@ fn integrate_arguments(module: &mut Module) {
         .argument(Argument::by_val("a"))
         .argument(Argument::by_val_optional("b"));
 }
+
+fn integrate_typed_arguments(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_arguments_typed_scalar",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let a = arguments[0].expect_long()?;
+                let b = match arguments.get(1) {
+                    Some(b) if !b.get_type_info().is_null() => b.expect_z_str()?.to_str()?,
+                    _ => "null",
+                };
+                Ok(format!("{}: {}", a, b))
+            },
+        )
+        .argument(Argument::by_val("a").with_type(BuiltinType::Int))
+        .argument(
+            Argument::by_val_optional("b")
+                .with_type(TypeHint::from(BuiltinType::String).nullable()),
+        );
+
+    module
+        .add_function(
+            "integrate_arguments_typed_float",
+            |arguments: &mut [ZVal]| -> phper::Result<f64> { arguments[0].expect_double() },
+        )
+        .argument(Argument::by_val("a").with_type(BuiltinType::Float));
+
+    module
+        .add_function(
+            "integrate_arguments_typed_union",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                Ok(arguments[0].get_type_info().to_string())
+            },
+        )
+        .argument(
+            Argument::by_val("a").with_type(TypeHint::class("stdClass").or(BuiltinType::Array)),
+        );
+}
+
+fn integrate_default_arguments(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_arguments_default",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let a = arguments[0].expect_long()?;
+                let b = arguments[1].expect_long()?;
+                let c = arguments[2].expect_z_str()?.to_str()?;
+                arguments[3].expect_null()?;
+                Ok(format!("{}, {}, {}", a, b, c))
+            },
+        )
+        .argument(Argument::by_val("a"))
+        .argument(
+            Argument::by_val("b")
+                .with_type(BuiltinType::Int)
+                .with_default_value("100"),
+        )
+        .argument(Argument::by_val("c").with_default_value("PHP_OS_FAMILY"))
+        .argument(Argument::by_val("d").with_default_value("null"));
+}
+
+fn integrate_variadic_arguments(module: &mut Module) {
+    module
+        .add_variadic_function(
+            "integrate_arguments_variadic",
+            |arguments, values, named| -> phper::Result<String> {
+                let mut items = vec![arguments[0].expect_z_str()?.to_str()?.to_owned()];
+                for value in values.iter() {
+                    items.push(value.expect_long()?.to_string());
+                }
+                for (key, value) in named.iter() {
+                    let key = match key {
+                        IterKey::Index(i) => i.to_string(),
+                        IterKey::ZStr(s) => s.to_str()?.to_owned(),
+                    };
+                    items.push(format!("{}={}", key, value.expect_long()?));
+                }
+                Ok(items.join(","))
+            },
+        )
+        .argument(Argument::by_val("prefix").with_type(BuiltinType::String))
+        .argument(Argument::variadic("numbers").with_type(BuiltinType::Int));
+}
@@ -10,14 +10,19 @@
 
 use phper::alloc::RefClone;
 use phper::{
+    arrays::{InsertKey, ZArray},
     classes::{
         entity::ClassEntity,
         entry::ClassEntry,
+        handlers::{ArrayAccess, Countable, Operator, PropertyCheck, PropertyPurpose},
         zend_classes::{array_access_interface, iterator_interface},
-        InterfaceEntity, StaticInterface, StaticStateClass, Visibility,
+        InterfaceEntity, PhpClass, PropertyDefault, StaticInterface, StaticStateClass, Visibility,
     },
-    functions::Argument,
+    functions::{Argument, ZCallable},
     modules::Module,
+    objects::StateObj,
+    php_class, php_impl,
+    types::{BuiltinType, TypeHint, TypeInfo},
     values::ZVal,
 };
 use std::{collections::HashMap, convert::Infallible};
@@ -27,6 +32,16 @@ pub fn integrate(module: &mut Module) {
     integrate_foo(module);
     integrate_i_bar(module);
     integrate_static_props(module);
+    integrate_constants(module);
+    integrate_typed_props(module);
+    integrate_modifiers(module);
+    integrate_property_hooks(module);
+    integrate_operators(module);
+    integrate_native_interfaces(module);
+    integrate_gc(module);
+    integrate_constructor(module);
+    module.add_class(Counter::class_entity());
+    module.add_class(Callback::class_entity());
 }
 
 fn integrate_a(module: &mut Module) {
@@ -56,6 +71,24 @@ fn integrate_a(module: &mut Module) {
         Ok::<_, phper::Error>(format!("name: {}, number: {}", name, number))
     });
 
+    class
+        .add_typed_method(
+            "add",
+            Visibility::Public,
+            |this: &mut StateObj, delta: i64| -> phper::Result<i64> {
+                let number = this.get_property("number").expect_long()? + delta;
+                this.set_property("number", ZVal::from(number));
+                Ok(number)
+            },
+        )
+        .argument_names(["delta"]);
+
+    class.add_typed_static_method(
+        "greet",
+        Visibility::Public,
+        |name: &str| -> Result<String, Infallible> { Ok(format!("hello, {}", name)) },
+    );
+
     module.add_class(class);
 }
 
@@ -155,6 +188,8 @@ fn integrate_i_bar(module: &mut Module) {
         .add_method("doSomethings")
         .argument(Argument::by_val("job_name"));
 
+    interface.add_constant("VERSION", "1.0");
+
     module.add_interface(interface);
 }
 
@@ -183,3 +218,474 @@ fn integrate_static_props(module: &mut Module) {
 
     module.add_class(class);
 }
+
+fn integrate_constants(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\ConstantsHolder");
+
+    class.add_constant("FLAG_NONE", 0, Visibility::Public);
+    class.add_constant("FLAG_ALL", 0xff, Visibility::Public);
+    class.add_constant("RATIO", 0.5, Visibility::Public);
+    class.add_constant("NAME", "holder", Visibility::Protected);
+    class.add_constant("SECRET", true, Visibility::Private);
+    class.add_constant("NOTHING", (), Visibility::Public);
+    let last = class
+        .add_constant("LAST", "last", Visibility::Public)
+        .set_final();
+    if phper::sys::PHP_VERSION_ID >= 80300 {
+        last.type_hint(BuiltinType::String);
+    }
+
+    module.add_class(class);
+}
+
+fn integrate_typed_props(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\TypedProps");
+
+    class
+        .add_property("count", Visibility::Public, 0)
+        .type_hint(BuiltinType::Int);
+    class.add_typed_property(
+        "label",
+        Visibility::Public,
+        TypeHint::from(BuiltinType::String).nullable(),
+    );
+    class.add_typed_property(
+        "key",
+        Visibility::Public,
+        TypeHint::from(BuiltinType::Int).or(BuiltinType::String),
+    );
+    class
+        .add_property("obj", Visibility::Public, ())
+        .type_hint(TypeHint::class("stdClass").nullable());
+    class
+        .add_typed_property("id", Visibility::Public, BuiltinType::Int)
+        .set_readonly();
+    class
+        .add_static_property("instances", Visibility::Public, 0)
+        .type_hint(BuiltinType::Int);
+
+    class.add_constant("LIMIT", 10, Visibility::Public);
+    class
+        .add_typed_property("items", Visibility::Public, BuiltinType::Array)
+        .default_value(PropertyDefault::List(vec![]));
+    class
+        .add_property("options", Visibility::Public, ())
+        .default_value(PropertyDefault::Map(vec![
+            (
+                "limit".to_owned(),
+                PropertyDefault::Constant("self::LIMIT".to_owned()),
+            ),
+            ("tags".to_owned(), vec!["a".into(), "b".into()].into()),
+            (
+                "eol".to_owned(),
+                PropertyDefault::Constant("PHP_EOL".to_owned()),
+            ),
+        ]));
+    class
+        .add_static_property("registry", Visibility::Public, ())
+        .default_value(vec![1.into(), 2.into()]);
+
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            this.set_property("id", arguments[0].clone());
+            Ok::<_, Infallible>(())
+        })
+        .argument(Argument::by_val("id"));
+
+    module.add_class(class);
+}
+
+fn integrate_modifiers(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\AbstractShape");
+    class.set_abstract();
+    class.add_abstract_method("area", Visibility::Public);
+    class
+        .add_method("describe", Visibility::Public, |_, _| {
+            Ok::<_, Infallible>("shape")
+        })
+        .set_final();
+    module.add_class(class);
+
+    let mut class = ClassEntity::new("IntegrationTest\\FinalPoint");
+    class.set_final();
+    module.add_class(class);
+
+    if phper::sys::PHP_VERSION_ID >= 80200 {
+        let mut class = ClassEntity::new("IntegrationTest\\ReadonlyPoint");
+        class.set_readonly();
+        class.add_typed_property("x", Visibility::Public, BuiltinType::Int);
+        class
+            .add_method("__construct", Visibility::Public, |this, arguments| {
+                this.set_property("x", arguments[0].clone());
+                Ok::<_, Infallible>(())
+            })
+            .argument(Argument::by_val("x"));
+        module.add_class(class);
+    }
+}
+
+struct Point {
+    x: i64,
+    tags: ZVal,
+}
+
+fn integrate_property_hooks(module: &mut Module) {
+    let mut class = ClassEntity::new_with_state_constructor("IntegrationTest\\Point", || Point {
+        x: 0,
+        tags: ZArray::new().into(),
+    });
+
+    class.add_property("label", Visibility::Public, "point");
+
+    class.read_property(|this, name| {
+        let point = this.as_state::<Point>();
+        Ok::<_, phper::Error>(match name.to_str()? {
+            "x" => Some(ZVal::from(point.x)),
+            "tags" => Some(point.tags.clone()),
+            _ => None,
+        })
+    });
+    class.write_property(|this, name, value| {
+        let point = this.as_mut_state::<Point>();
+        match name.to_str()? {
+            "x" => match value.as_long() {
+                Some(x) => point.x = x,
+                None => return Err(phper::Error::boxed("x must be int")),
+            },
+            "tags" => point.tags = value.clone(),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    class.has_property(|_, name, check| {
+        Ok::<_, phper::Error>(match (name.to_str()?, check) {
+            ("secret", PropertyCheck::Exists) => Some(true),
+            ("secret", _) => Some(false),
+            _ => None,
+        })
+    });
+    class.unset_property(|this, name| {
+        let point = this.as_mut_state::<Point>();
+        Ok::<_, phper::Error>(match name.to_str()? {
+            "x" => {
+                point.x = 0;
+                true
+            }
+            _ => false,
+        })
+    });
+    class.get_property_ptr_ptr(|this, name| match name.to_str() {
+        Ok("tags") => Some(&mut this.as_mut_state::<Point>().tags),
+        _ => None,
+    });
+    class.get_debug_info(|this| {
+        let point = this.as_state::<Point>();
+        let mut arr = ZArray::new();
+        arr.insert("x", point.x);
+        arr.insert("tags", point.tags.clone());
+        Ok::<_, Infallible>(arr)
+    });
+    class.get_properties_for(|this, purpose| {
+        let point = this.as_state::<Point>();
+        Ok::<_, Infallible>(match purpose {
+            PropertyPurpose::ArrayCast | PropertyPurpose::Json => {
+                let mut arr = ZArray::new();
+                arr.insert("x", point.x);
+                arr.insert("tags", point.tags.clone());
+                Some(arr)
+            }
+            _ => None,
+        })
+    });
+
+    module.add_class(class);
+}
+
+static MONEY_CLASS: StaticStateClass = StaticStateClass::null();
+
+/// The amount of money operand, in cents.
+fn money_amount(op: &ZVal) -> Option<i64> {
+    match op.as_z_obj() {
+        Some(obj) if obj.get_class().is_instance_of(MONEY_CLASS.as_class_entry()) => {
+            Some(*unsafe { obj.as_state_obj() }.as_state::<i64>())
+        }
+        Some(_) => None,
+        None => op.as_long(),
+    }
+}
+
+fn integrate_operators(module: &mut Module) {
+    let mut class =
+        ClassEntity::new_with_default_state_constructor::<i64>("IntegrationTest\\Money");
+    class.bind(&MONEY_CLASS);
+
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            *this.as_mut_state::<i64>() = arguments[0].expect_long()?;
+            Ok::<_, phper::Error>(())
+        })
+        .argument(Argument::by_val("cents"));
+
+    class.add_method("cents", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(*this.as_state::<i64>())
+    });
+
+    class.do_operation(|operator, op1, op2| {
+        let (Some(lhs), Some(rhs)) = (money_amount(op1), money_amount(op2)) else {
+            return Ok(None);
+        };
+        let cents = match operator {
+            Operator::Add => lhs + rhs,
+            Operator::Sub => lhs - rhs,
+            Operator::Mul if op1.as_long().is_some() || op2.as_long().is_some() => lhs * rhs,
+            _ => return Ok(None),
+        };
+        let mut money = MONEY_CLASS.init_object()?;
+        *money.as_mut_state::<i64>() = cents;
+        Ok::<_, phper::Error>(Some(money.into_z_object().into()))
+    });
+    class.compare(|op1, op2| {
+        Ok::<_, Infallible>(match (money_amount(op1), money_amount(op2)) {
+            (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+            _ => None,
+        })
+    });
+    class.cast_object(|this, type_info| {
+        let cents = *this.as_state::<i64>();
+        Ok::<_, Infallible>(match type_info {
+            TypeInfo::LONG => Some(ZVal::from(cents)),
+            TypeInfo::DOUBLE => Some(ZVal::from(cents as f64 / 100.)),
+            TypeInfo::BOOL => Some(ZVal::from(cents != 0)),
+            _ => None,
+        })
+    });
+    class.to_string(|this| {
+        let cents = *this.as_state::<i64>();
+        Ok::<_, Infallible>(format!("${}.{:02}", cents / 100, cents % 100))
+    });
+
+    module.add_class(class);
+}
+
+#[derive(Default)]
+struct Bag {
+    items: Vec<ZVal>,
+}
+
+impl Bag {
+    fn index(&self, offset: &ZVal) -> phper::Result<usize> {
+        let index = offset.expect_long()?;
+        if index < 0 || index as usize >= self.items.len() {
+            return Err(phper::Error::boxed(format!(
+                "offset {} out of range",
+                index
+            )));
+        }
+        Ok(index as usize)
+    }
+}
+
+impl Countable for Bag {
+    fn count(&mut self) -> phper::Result<i64> {
+        Ok(self.items.len() as i64)
+    }
+}
+
+impl ArrayAccess for Bag {
+    fn offset_exists(&mut self, offset: &ZVal) -> phper::Result<bool> {
+        Ok(self.index(offset).is_ok())
+    }
+
+    fn offset_get(&mut self, offset: &ZVal) -> phper::Result<ZVal> {
+        let index = self.index(offset)?;
+        Ok(self.items[index].clone())
+    }
+
+    fn offset_set(&mut self, offset: Option<&ZVal>, value: ZVal) -> phper::Result<()> {
+        match offset {
+            Some(offset) => {
+                let index = self.index(offset)?;
+                self.items[index] = value;
+            }
+            None => self.items.push(value),
+        }
+        Ok(())
+    }
+
+    fn offset_unset(&mut self, offset: &ZVal) -> phper::Result<()> {
+        let index = self.index(offset)?;
+        self.items.remove(index);
+        Ok(())
+    }
+}
+
+fn integrate_native_interfaces(module: &mut Module) {
+    let mut class = ClassEntity::new_with_default_state_constructor::<Bag>("IntegrationTest\\Bag");
+    class.implements_countable::<Bag>();
+    class.implements_array_access::<Bag>();
+    class.serializable(
+        |this| {
+            let mut arr = ZArray::new();
+            for item in &this.as_state::<Bag>().items {
+                arr.insert(InsertKey::NextIndex, item.clone());
+            }
+            Ok(arr)
+        },
+        |this, data| {
+            let bag = this.as_mut_state::<Bag>();
+            bag.items = data.iter().map(|(_, item)| item.clone()).collect();
+            Ok::<_, Infallible>(())
+        },
+    );
+    module.add_class(class);
+
+    // The state isn't the type implementing the interfaces.
+    let mut class =
+        ClassEntity::new_with_default_state_constructor::<i64>("IntegrationTest\\MismatchedBag");
+    class.implements_countable::<Bag>();
+    class.implements_array_access::<Bag>();
+    module.add_class(class);
+
+    let mut class =
+        ClassEntity::new_with_default_state_constructor::<(i64, i64)>("IntegrationTest\\Range");
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            let start = arguments[0].expect_long()?;
+            let end = arguments[1].expect_long()?;
+            *this.as_mut_state::<(i64, i64)>() = (start, end);
+            Ok::<_, phper::Error>(())
+        })
+        .arguments([Argument::by_val("start"), Argument::by_val("end")]);
+    class.get_iterator(|this| {
+        let (start, end) = *this.as_state::<(i64, i64)>();
+        if start > end {
+            return Err(phper::Error::boxed("start is greater than end"));
+        }
+        Ok((start..end).map(|i| (format!("k{}", i), i)))
+    });
+    module.add_class(class);
+}
+
+fn integrate_gc(module: &mut Module) {
+    let mut class =
+        ClassEntity::new_with_default_state_constructor::<Option<ZVal>>("IntegrationTest\\Holder");
+    class
+        .add_method("hold", Visibility::Public, |this, arguments| {
+            *this.as_mut_state::<Option<ZVal>>() = Some(arguments[0].clone());
+            Ok::<_, Infallible>(())
+        })
+        .argument(Argument::by_val("value"));
+    class.get_gc(|this, gc| {
+        if let Some(value) = this.as_state::<Option<ZVal>>() {
+            gc.add_zval(value);
+        }
+    });
+    module.add_class(class);
+}
+
+struct Interval {
+    start: i64,
+    end: i64,
+}
+
+fn integrate_constructor(module: &mut Module) {
+    let mut class = ClassEntity::new_with_constructor(
+        "IntegrationTest\\Interval",
+        [
+            Argument::by_val("start").with_type(BuiltinType::Int),
+            Argument::by_val("end").with_type(BuiltinType::Int),
+        ],
+        |arguments| {
+            let start = arguments[0].expect_long()?;
+            let end = arguments[1].expect_long()?;
+            if start > end {
+                return Err(phper::Error::boxed("start is greater than end"));
+            }
+            Ok(Interval { start, end })
+        },
+    );
+    class
+        .add_method("length", Visibility::Public, |this, _| {
+            let interval = this.as_state::<Interval>();
+            Ok::<_, Infallible>(interval.end - interval.start)
+        })
+        .return_type(BuiltinType::Int);
+    class.serializable_with_constructor(
+        |this| {
+            let interval = this.try_as_state::<Interval>()?;
+            let mut arr = ZArray::new();
+            arr.insert("start", interval.start);
+            arr.insert("end", interval.end);
+            Ok(arr)
+        },
+        |data| {
+            let get = |key: &str| match data.get(key) {
+                Some(value) => value.expect_long(),
+                None => Err(phper::Error::boxed(format!("missing {}", key))),
+            };
+            Ok::<_, phper::Error>(Interval {
+                start: get("start")?,
+                end: get("end")?,
+            })
+        },
+    );
+    module.add_class(class);
+}
+
+#[php_class(
+    name = "IntegrationTest\\Counter",
+    property(name = "label", default = "counter"),
+    property(name = "instances", default = 0, protected, static)
+)]
+struct Counter {
+    count: i64,
+}
+
+#[php_impl]
+impl Counter {
+    #[php(constructor)]
+    fn new(start: Option<i64>) -> Self {
+        Self {
+            count: start.unwrap_or(0),
+        }
+    }
+
+    fn increase(&mut self, step: Option<i64>) -> i64 {
+        self.count += step.unwrap_or(1);
+        self.count
+    }
+
+    #[php(name = "getCount")]
+    fn count(&self) -> i64 {
+        self.count
+    }
+
+    #[php(private)]
+    fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    fn between(start: i64, end: i64) -> phper::Result<i64> {
+        if start > end {
+            return Err(phper::Error::boxed("start is greater than end"));
+        }
+        Ok(end - start)
+    }
+}
+
+#[php_class(name = "IntegrationTest\\Callback")]
+struct Callback {
+    callable: ZCallable,
+}
+
+#[php_impl]
+impl Callback {
+    #[php(constructor)]
+    fn new(callable: ZCallable) -> Self {
+        Self { callable }
+    }
+
+    fn invoke(&mut self, value: ZVal) -> phper::Result<ZVal> {
+        self.callable.call([value])
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    classes::{InterfaceEntity, StaticInterface, Visibility},
+    enums::{EnumEntity, StaticEnum},
+    functions::Argument,
+    modules::Module,
+};
+use std::convert::Infallible;
+
+static HAS_LABEL_INTERFACE: StaticInterface = StaticInterface::null();
+
+static STATUS_ENUM: StaticEnum = StaticEnum::null();
+
+pub fn integrate(module: &mut Module) {
+    integrate_has_label(module);
+    integrate_suit(module);
+    integrate_status(module);
+    integrate_level(module);
+
+    module.add_function("integrate_enums_status_active", |_| {
+        STATUS_ENUM.get_case("Active")
+    });
+
+    module
+        .add_function("integrate_enums_status_case", |arguments| {
+            let name = arguments[0].expect_z_str()?.to_str()?.to_owned();
+            STATUS_ENUM.get_case(name)
+        })
+        .argument(Argument::by_val("name"));
+
+    module
+        .add_function("integrate_enums_backing_value", |arguments| {
+            let case = arguments[0].expect_z_obj()?;
+            Ok::<_, phper::Error>(case.get_enum_backing_value().cloned())
+        })
+        .argument(Argument::by_val("case"));
+
+    module
+        .add_function("integrate_enums_case_name", |arguments| {
+            let case = arguments[0].expect_z_obj()?;
+            let name = case
+                .get_enum_case_name()
+                .map(|name| name.to_str().map(ToOwned::to_owned))
+                .transpose()?;
+            Ok::<_, phper::Error>(name)
+        })
+        .argument(Argument::by_val("case"));
+}
+
+fn integrate_has_label(module: &mut Module) {
+    let mut interface = InterfaceEntity::new(r"IntegrationTest\HasLabel");
+    interface.bind(&HAS_LABEL_INTERFACE);
+    interface.add_method("label");
+    module.add_interface(interface);
+}
+
+fn integrate_suit(module: &mut Module) {
+    let mut suit = EnumEntity::new(r"IntegrationTest\Suit");
+    suit.add_case("Hearts", ());
+    suit.add_case("Spades", ());
+    suit.add_constant("DEFAULT_NAME", "Hearts", Visibility::Public);
+    suit.add_method("color", Visibility::Public, |this, _| {
+        let color = match this.get_enum_case_name().unwrap().to_str()? {
+            "Hearts" => "Red",
+            _ => "Black",
+        };
+        Ok::<_, phper::Error>(color)
+    });
+    suit.add_static_method("wild", Visibility::Public, |_| Ok::<_, Infallible>("Joker"));
+    module.add_enum(suit);
+}
+
+fn integrate_status(module: &mut Module) {
+    let mut status = EnumEntity::<i64>::new(r"IntegrationTest\Status");
+    status.bind(&STATUS_ENUM);
+    status.implements(|| HAS_LABEL_INTERFACE.as_class_entry());
+    status.add_case("Active", 1);
+    status.add_case("Inactive", 0);
+    status.add_method("label", Visibility::Public, |this, _| {
+        let value = this.get_enum_backing_value().unwrap().expect_long()?;
+        Ok::<_, phper::Error>(format!("status {}", value))
+    });
+    module.add_enum(status);
+}
+
+fn integrate_level(module: &mut Module) {
+    let mut level = EnumEntity::<String>::new(r"IntegrationTest\Level");
+    level.add_case("Low", "low").add_case("High", "high");
+    module.add_enum(level);
+}
@@ -9,15 +9,21 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
-    arrays::ZArray,
+    arrays::{InsertKey, ZArr, ZArray},
     errors::throw,
-    functions::{call, Argument},
+    functions::{call, call_with_named, Argument, ZCallable},
     modules::Module,
+    php_function,
+    types::{BuiltinType, TypeHint},
     values::ZVal,
 };
-use std::{convert::Infallible, io};
+use std::{convert::Infallible, io, rc::Rc};
 
 pub fn integrate(module: &mut Module) {
+    module.add_function_entity(integrate_functions_attr_join_entity());
+    module.add_function_entity(integrate_functions_attr_add_entity());
+    module.add_function_entity(integrate_functions_attr_noop_entity());
+
     module.add_function(
         "integrate_functions_call",
         |_: &mut [ZVal]| -> phper::Result<()> {
@@ -30,6 +36,51 @@ pub fn integrate(module: &mut Module) {
         },
     );
 
+    module.add_function(
+        "integrate_functions_call_named",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut named = ZArray::new();
+            named.insert("pad_type", ZVal::from(0));
+            let ret = call_with_named("str_pad", [ZVal::from("a"), ZVal::from(3)], &named)?;
+            assert_eq!(ret.expect_z_str()?.to_str()?, "  a");
+
+            let mut named = ZArray::new();
+            named.insert("length", ZVal::from(3));
+            named.insert("pad_string", ZVal::from("-"));
+            let ret = ZVal::from("str_pad").call_with_named([ZVal::from("a")], &named)?;
+            assert_eq!(ret.expect_z_str()?.to_str()?, "a--");
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_functions_call_by_ref",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut arguments = [
+                ZVal::from("/(\\d+)/"),
+                ZVal::from("abc123"),
+                ZVal::default(),
+            ];
+            assert_eq!(call("preg_match", &mut arguments)?.expect_long()?, 1);
+            let matches = arguments[2].expect_z_arr()?;
+            assert_eq!(matches.get(1u64).unwrap().expect_z_str()?.to_str()?, "123");
+
+            let mut arr = ZArray::new();
+            arr.insert(InsertKey::NextIndex, ZVal::from(3));
+            arr.insert(InsertKey::NextIndex, ZVal::from(1));
+            arr.insert(InsertKey::NextIndex, ZVal::from(2));
+            let mut arguments = [ZVal::from(arr)];
+            ZCallable::new("sort")?.call(&mut arguments)?;
+            let values = arguments[0]
+                .expect_z_arr()?
+                .iter()
+                .map(|(_, val)| val.expect_long())
+                .collect::<phper::Result<Vec<_>>>()?;
+            assert_eq!(values, [1, 2, 3]);
+            Ok(())
+        },
+    );
+
     module
         .add_function(
             "integrate_functions_call_callable",
@@ -59,4 +110,124 @@ pub fn integrate(module: &mut Module) {
         assert_eq!(e.to_string(), "throw error exception");
         Ok::<_, Infallible>(())
     });
+
+    module
+        .add_function("integrate_functions_return_type_int", |_| {
+            Ok::<_, Infallible>(1)
+        })
+        .return_type(BuiltinType::Int);
+
+    module
+        .add_function("integrate_functions_return_type_void", |_| {
+            Ok::<_, Infallible>(())
+        })
+        .return_type(BuiltinType::Void);
+
+    module
+        .add_function("integrate_functions_return_type_nullable", |_| {
+            Ok::<_, Infallible>(())
+        })
+        .return_type(TypeHint::class("stdClass").nullable());
+
+    module
+        .add_function("integrate_functions_return_type_mismatch", |_| {
+            Ok::<_, Infallible>("foo")
+        })
+        .return_type(BuiltinType::Int);
+
+    module
+        .add_typed_function(
+            "integrate_functions_typed_repeat",
+            |s: &str, times: i64, separator: Option<&str>| -> phper::Result<String> {
+                Ok(vec![s; times as usize].join(separator.unwrap_or("")))
+            },
+        )
+        .argument_names(["s", "times", "separator"]);
+
+    module.add_typed_function(
+        "integrate_functions_typed_count",
+        |arr: Option<&ZArr>, default: i64| -> Result<i64, Infallible> {
+            Ok(arr.map(|arr| arr.iter().count() as i64).unwrap_or(default))
+        },
+    );
+
+    module
+        .add_typed_function(
+            "integrate_functions_callable_sum",
+            |mut callable: ZCallable, items: &ZArr| -> phper::Result<i64> {
+                let mut sum = 0;
+                for (_, item) in items.iter() {
+                    sum += callable.call([item.clone()])?.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .argument_names(["fn", "items"]);
+
+    module.add_function("integrate_functions_callable_new", |arguments| {
+        ZCallable::new(arguments[0].clone()).map(|_| ())
+    });
+
+    module.add_function("integrate_functions_closure_prefix", |arguments| {
+        let prefix = arguments[0].expect_z_str()?.to_str()?.to_owned();
+        Ok::<_, phper::Error>(ZVal::from_closure(
+            [Argument::by_val("name")],
+            move |arguments| {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                Ok::<_, phper::Error>(format!("{}{}", prefix, name))
+            },
+        ))
+    });
+
+    module.add_function("integrate_functions_closure_typed", |_| {
+        Ok::<_, Infallible>(ZVal::from_typed_closure(
+            |a: i64, b: Option<i64>| -> Result<i64, Infallible> { Ok(a * b.unwrap_or(2)) },
+        ))
+    });
+
+    module.add_function("integrate_functions_closure_drop", |_| {
+        let captured = Rc::new(());
+        let weak = Rc::downgrade(&captured);
+        let closure = ZVal::from_closure([], move |_| {
+            let _ = &captured;
+            Ok::<_, Infallible>(())
+        });
+        assert_eq!(weak.strong_count(), 1);
+        drop(closure);
+        assert_eq!(weak.strong_count(), 0);
+        Ok::<_, Infallible>(())
+    });
+
+    module.add_function("integrate_functions_closure_bound_drop", |_| {
+        let captured = Rc::new(());
+        let weak = Rc::downgrade(&captured);
+        let closure = ZVal::from_closure([], move |_| {
+            let _ = &captured;
+            Ok::<_, Infallible>("called")
+        });
+        let mut bound = call("Closure::bind", &mut [closure, ZVal::from(())])?;
+        assert_eq!(weak.strong_count(), 1);
+        assert_eq!(bound.call([])?.expect_z_str()?.to_str()?, "called");
+        drop(bound);
+        assert_eq!(weak.strong_count(), 0);
+        Ok::<_, phper::Error>(())
+    });
+}
+
+/// Join the strings with separator.
+#[php_function]
+fn integrate_functions_attr_join(pieces: &ZArr, separator: Option<&str>) -> phper::Result<String> {
+    let pieces = pieces
+        .iter()
+        .map(|(_, piece)| Ok(piece.expect_z_str()?.to_str()?.to_owned()))
+        .collect::<phper::Result<Vec<_>>>()?;
+    Ok(pieces.join(separator.unwrap_or(",")))
 }
+
+#[php_function(name = "integrate_functions_attr_plus")]
+fn integrate_functions_attr_add(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+#[php_function]
+fn integrate_functions_attr_noop() {}
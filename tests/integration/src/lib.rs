@@ -14,6 +14,7 @@ mod arguments;
 mod arrays;
 mod classes;
 mod constants;
+mod enums;
 mod errors;
 mod functions;
 mod ini;
@@ -40,6 +41,7 @@ pub fn get_module() -> Module {
     strings::integrate(&mut module);
     values::integrate(&mut module);
     constants::integrate(&mut module);
+    enums::integrate(&mut module);
     ini::integrate(&mut module);
     errors::integrate(&mut module);
     references::integrate(&mut module);
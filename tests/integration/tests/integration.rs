@@ -37,6 +37,7 @@ fn test_cli() {
             &tests_php_dir.join("strings.php"),
             &tests_php_dir.join("values.php"),
             &tests_php_dir.join("constants.php"),
+            &tests_php_dir.join("enums.php"),
             &tests_php_dir.join("ini.php"),
             &tests_php_dir.join("references.php"),
             &tests_php_dir.join("errors.php"),
@@ -67,5 +68,6 @@ fn test_fpm() {
     test_fpm_request("GET", &tests_php_dir, "/strings.php", None, None);
     test_fpm_request("GET", &tests_php_dir, "/values.php", None, None);
     test_fpm_request("GET", &tests_php_dir, "/constants.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/enums.php", None, None);
     test_fpm_request("GET", &tests_php_dir, "/ini.php", None, None);
 }
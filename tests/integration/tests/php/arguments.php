@@ -47,3 +47,59 @@ assert_throw(function () { integrate_arguments_optional(); }, $argumentCountErro
 assert_eq(integrate_arguments_optional("foo"), "foo: false");
 assert_eq(integrate_arguments_optional("foo", true), "foo: true");
 assert_eq(integrate_arguments_optional("foo", true, "bar"), "foo: true");
+
+// Typed arguments.
+function assert_type_error($callable, $expect_message_prefix) {
+    try {
+        $callable();
+        throw new AssertionError("`{$expect_message_prefix}` not throws");
+    } catch (TypeError $e) {
+        if (strpos($e->getMessage(), $expect_message_prefix) !== 0) {
+            throw new AssertionError(sprintf("expect exception message starts with `%s`, found `%s`", $expect_message_prefix, $e->getMessage()));
+        }
+    }
+}
+
+assert_eq(integrate_arguments_typed_scalar(1), "1: null");
+assert_eq(integrate_arguments_typed_scalar(1, null), "1: null");
+assert_eq(integrate_arguments_typed_scalar("2", "foo"), "2: foo");
+assert_eq(integrate_arguments_typed_scalar(3, 4), "3: 4");
+assert_type_error(function () { integrate_arguments_typed_scalar("foo"); }, 'integrate_arguments_typed_scalar(): Argument #1 ($a) must be of type int, string given');
+assert_type_error(function () { integrate_arguments_typed_scalar(null); }, 'integrate_arguments_typed_scalar(): Argument #1 ($a) must be of type int, null given');
+assert_type_error(function () { integrate_arguments_typed_scalar(1, []); }, 'integrate_arguments_typed_scalar(): Argument #2 ($b) must be of type ?string, array given');
+
+assert_eq(integrate_arguments_typed_float(1), 1.0);
+
+assert_eq(integrate_arguments_typed_union(new stdClass()), "object");
+assert_eq(integrate_arguments_typed_union([]), "array");
+assert_type_error(function () { integrate_arguments_typed_union(new ArrayObject()); }, 'integrate_arguments_typed_union(): Argument #1 ($a) must be of type stdClass|array, ArrayObject given');
+
+$parameters = (new ReflectionFunction("integrate_arguments_typed_scalar"))->getParameters();
+assert_eq((string) $parameters[0]->getType(), "int");
+assert_eq((string) $parameters[1]->getType(), "?string");
+assert_true($parameters[1]->allowsNull());
+$parameters = (new ReflectionFunction("integrate_arguments_typed_union"))->getParameters();
+assert_eq((string) $parameters[0]->getType(), "stdClass|array");
+
+// Default values of arguments.
+assert_eq(integrate_arguments_default(1), "1, 100, " . PHP_OS_FAMILY);
+assert_eq(integrate_arguments_default(1, 2), "1, 2, " . PHP_OS_FAMILY);
+assert_eq(integrate_arguments_default(1, 2, "foo"), "1, 2, foo");
+assert_eq(integrate_arguments_default(1, c: "bar"), "1, 100, bar");
+$parameters = (new ReflectionFunction("integrate_arguments_default"))->getParameters();
+assert_false($parameters[0]->isOptional());
+assert_true($parameters[1]->isOptional());
+assert_eq($parameters[1]->getDefaultValue(), 100);
+assert_eq($parameters[2]->getDefaultValueConstantName(), "PHP_OS_FAMILY");
+assert_eq($parameters[3]->getDefaultValue(), null);
+
+// Variadic arguments.
+assert_eq(integrate_arguments_variadic("a"), "a");
+assert_eq(integrate_arguments_variadic("a", 1, "2", 3), "a,1,2,3");
+assert_eq(integrate_arguments_variadic("a", 1, x: 2, y: 3), "a,1,x=2,y=3");
+assert_eq(integrate_arguments_variadic(...["prefix" => "b", "z" => 4]), "b,z=4");
+assert_type_error(function () { integrate_arguments_variadic("a", 1, "foo"); }, 'integrate_arguments_variadic(): Argument #3 must be of type int, string given');
+$parameters = (new ReflectionFunction("integrate_arguments_variadic"))->getParameters();
+assert_eq(count($parameters), 2);
+assert_true($parameters[1]->isVariadic());
+assert_eq((string) $parameters[1]->getType(), "int");
@@ -23,6 +23,10 @@ assert_throw(function () { new \IntegrationTest\A(); }, $argumentCountErrorName,
 
 $a = new \IntegrationTest\A("foo", 99);
 assert_eq($a->speak(), "name: foo, number: 99");
+assert_eq($a->add(1), 100);
+assert_eq($a->speak(), "name: foo, number: 100");
+assert_eq(\IntegrationTest\A::greet("bar"), "hello, bar");
+assert_throw(function () use ($a) { $a->add("x"); }, "TypeError", 0, 'IntegrationTest\A::add(): Argument #1 ($delta) must be of type int, string given');
 
 $reflection_class = new ReflectionClass(\IntegrationTest\A::class);
 
@@ -61,6 +65,234 @@ $doSomethings = $interface->getMethod("doSomethings");
 assert_true($doSomethings->isPublic());
 assert_true($doSomethings->isAbstract());
 
+assert_eq(\IntegrationTest\IBar::VERSION, "1.0");
+
+// Test class constants.
+assert_eq(\IntegrationTest\ConstantsHolder::FLAG_NONE, 0);
+assert_eq(\IntegrationTest\ConstantsHolder::FLAG_ALL, 255);
+assert_eq(\IntegrationTest\ConstantsHolder::RATIO, 0.5);
+assert_eq(\IntegrationTest\ConstantsHolder::NOTHING, null);
+assert_eq(\IntegrationTest\ConstantsHolder::LAST, "last");
+
+$reflection_class = new ReflectionClass(\IntegrationTest\ConstantsHolder::class);
+assert_true($reflection_class->getReflectionConstant("NAME")->isProtected());
+assert_eq($reflection_class->getConstant("NAME"), "holder");
+assert_true($reflection_class->getReflectionConstant("SECRET")->isPrivate());
+assert_eq($reflection_class->getConstant("SECRET"), true);
+assert_true($reflection_class->getReflectionConstant("LAST")->isFinal());
+if (PHP_VERSION_ID >= 80300) {
+    assert_eq((string) $reflection_class->getReflectionConstant("LAST")->getType(), "string");
+}
+
+// Test typed properties.
+$props = new \IntegrationTest\TypedProps(7);
+assert_eq($props->count, 0);
+assert_eq($props->obj, null);
+assert_eq($props->id, 7);
+assert_eq(\IntegrationTest\TypedProps::$instances, 0);
+assert_throw(function () use ($props) { return $props->label; }, "Error", 0, 'Typed property IntegrationTest\TypedProps::$label must not be accessed before initialization');
+$props->label = "foo";
+assert_eq($props->label, "foo");
+$props->key = "k";
+assert_eq($props->key, "k");
+assert_throw(function () use ($props) { $props->count = "foo"; }, "TypeError", 0, 'Cannot assign string to property IntegrationTest\TypedProps::$count of type int');
+assert_throw(function () use ($props) { $props->key = []; }, "TypeError", 0, 'Cannot assign array to property IntegrationTest\TypedProps::$key of type string|int');
+assert_throw(function () use ($props) { $props->id = 8; }, "Error", 0, 'Cannot modify readonly property IntegrationTest\TypedProps::$id');
+
+$reflection_class = new ReflectionClass(\IntegrationTest\TypedProps::class);
+assert_eq((string) $reflection_class->getProperty("count")->getType(), "int");
+assert_eq((string) $reflection_class->getProperty("label")->getType(), "?string");
+assert_eq((string) $reflection_class->getProperty("obj")->getType(), "?stdClass");
+assert_false($reflection_class->getProperty("label")->hasDefaultValue());
+assert_true($reflection_class->getProperty("id")->isReadOnly());
+assert_true($reflection_class->getProperty("instances")->isStatic());
+
+// Test non-scalar property defaults.
+assert_eq($props->items, []);
+assert_eq($props->options, ["limit" => 10, "tags" => ["a", "b"], "eol" => PHP_EOL]);
+$props->items[] = 1;
+$props->options["tags"][] = "c";
+assert_eq($props->items, [1]);
+assert_eq($props->options["tags"], ["a", "b", "c"]);
+$other_props = new \IntegrationTest\TypedProps(8);
+assert_eq($other_props->items, []);
+assert_eq($other_props->options["tags"], ["a", "b"]);
+assert_eq(\IntegrationTest\TypedProps::$registry, [1, 2]);
+\IntegrationTest\TypedProps::$registry[] = 3;
+assert_eq(\IntegrationTest\TypedProps::$registry, [1, 2, 3]);
+assert_eq($reflection_class->getProperty("items")->getDefaultValue(), []);
+
+// Test class and method modifiers.
+assert_throw(function () { new \IntegrationTest\AbstractShape(); }, "Error", 0, "Cannot instantiate abstract class IntegrationTest\\AbstractShape");
+$reflection_class = new ReflectionClass(\IntegrationTest\AbstractShape::class);
+assert_true($reflection_class->isAbstract());
+assert_true($reflection_class->getMethod("area")->isAbstract());
+assert_true($reflection_class->getMethod("describe")->isFinal());
+$reflection_class = new ReflectionClass(\IntegrationTest\FinalPoint::class);
+assert_true($reflection_class->isFinal());
+assert_false($reflection_class->isAbstract());
+if (PHP_VERSION_ID >= 80200) {
+    $point = new \IntegrationTest\ReadonlyPoint(3);
+    assert_eq($point->x, 3);
+    assert_throw(function () use ($point) { $point->x = 4; }, "Error", 0, 'Cannot modify readonly property IntegrationTest\ReadonlyPoint::$x');
+    assert_throw(function () use ($point) { $point->y = 4; }, "Error", 0, 'Cannot create dynamic property IntegrationTest\ReadonlyPoint::$y');
+    $reflection_class = new ReflectionClass(\IntegrationTest\ReadonlyPoint::class);
+    assert_true($reflection_class->isReadOnly());
+    assert_true($reflection_class->getProperty("x")->isReadOnly());
+}
+
+// Test property hooks.
+$point = new \IntegrationTest\Point();
+assert_eq($point->x, 0);
+$point->x = 3;
+assert_eq($point->x, 3);
+$point->x++;
+$point->x += 2;
+assert_eq($point->x, 6);
+assert_throw(function () use ($point) { $point->x = "foo"; }, "ErrorException", 0, "x must be int");
+assert_true(isset($point->x));
+assert_false(empty($point->x));
+unset($point->x);
+assert_eq($point->x, 0);
+assert_true(empty($point->x));
+$point->tags[] = "a";
+$point->tags[] = "b";
+assert_eq($point->tags, ["a", "b"]);
+$point->tags = ["c"];
+assert_eq($point->tags, ["c"]);
+assert_false(isset($point->secret));
+assert_true(property_exists($point, "secret"));
+assert_eq($point->label, "point");
+$point->label = "origin";
+assert_eq($point->label, "origin");
+assert_eq(get_object_vars($point), ["label" => "origin"]);
+
+// Test debug info and properties for hooks.
+$debug = print_r($point, true);
+assert_true(str_contains($debug, "[x] => 0"));
+assert_false(str_contains($debug, "[label]"));
+assert_eq((array) $point, ["x" => 0, "tags" => ["c"]]);
+assert_eq(json_encode($point), '{"x":0,"tags":["c"]}');
+assert_true(str_contains(var_export($point, true), "'label' => 'origin'"));
+
+// Test operator and comparison hooks.
+$a = new \IntegrationTest\Money(100);
+$b = new \IntegrationTest\Money(50);
+assert_eq(($a + $b)->cents(), 150);
+assert_eq(($a - 30)->cents(), 70);
+assert_eq((2 * $b)->cents(), 100);
+assert_eq((-$a)->cents(), -100);
+$c = $a;
+$c += $b;
+assert_eq($c->cents(), 150);
+assert_eq($a->cents(), 100);
+assert_throw(function () use ($a, $b) { return $a * $b; }, "TypeError", 0, 'Unsupported operand types: IntegrationTest\Money * IntegrationTest\Money');
+assert_true($a > $b);
+assert_true($a == new \IntegrationTest\Money(100));
+assert_true($a == 100);
+assert_false($a == $b);
+assert_eq($a <=> $b, 1);
+assert_eq($b <=> $a, -1);
+
+// Test cast hooks.
+assert_eq((string) $a, '$1.00');
+assert_eq("total: $c", 'total: $1.50');
+assert_true($a instanceof Stringable);
+assert_eq((int) $a, 100);
+assert_eq((float) $b, 0.5);
+assert_true((bool) $a);
+assert_false((bool) new \IntegrationTest\Money(0));
+
+// Test native Countable and ArrayAccess.
+$bag = new \IntegrationTest\Bag();
+assert_true($bag instanceof Countable);
+assert_true($bag instanceof ArrayAccess);
+assert_eq(count($bag), 0);
+$bag[] = "a";
+$bag[] = "b";
+assert_eq(count($bag), 2);
+assert_eq($bag->count(), 2);
+assert_eq($bag[1], "b");
+$bag[1] = "c";
+assert_eq($bag->offsetGet(1), "c");
+assert_true(isset($bag[0]));
+assert_false(isset($bag[2]));
+assert_false(empty($bag[0]));
+assert_eq($bag[2] ?? "none", "none");
+unset($bag[0]);
+assert_eq(count($bag), 1);
+assert_eq($bag[0], "c");
+assert_throw(function () use ($bag) { return $bag[5]; }, "ErrorException", 0, "offset 5 out of range");
+
+$mismatched = new \IntegrationTest\MismatchedBag();
+$message = 'State of object of class IntegrationTest\MismatchedBag is not integration::classes::Bag';
+assert_throw(function () use ($mismatched) { return count($mismatched); }, "Error", 0, $message);
+assert_throw(function () use ($mismatched) { return $mismatched->count(); }, "Error", 0, $message);
+assert_throw(function () use ($mismatched) { return $mismatched[0]; }, "Error", 0, $message);
+assert_throw(function () use ($mismatched) { $mismatched[] = 1; }, "Error", 0, $message);
+assert_throw(function () use ($mismatched) { return isset($mismatched[0]); }, "Error", 0, $message);
+
+// Test serializing state.
+$bag[] = ["d" => 1];
+$copied = unserialize(serialize($bag));
+assert_true($copied instanceof \IntegrationTest\Bag);
+assert_eq(count($copied), 2);
+assert_eq($copied[0], "c");
+assert_eq($copied[1], ["d" => 1]);
+
+// Test native iteration.
+$range = new \IntegrationTest\Range(1, 4);
+assert_true($range instanceof Traversable);
+$tmp_arr = [];
+foreach ($range as $key => $value) {
+    $tmp_arr[$key] = $value;
+}
+assert_eq($tmp_arr, ["k1" => 1, "k2" => 2, "k3" => 3]);
+assert_eq(iterator_to_array($range), ["k1" => 1, "k2" => 2, "k3" => 3]);
+assert_eq(iterator_to_array(new \IntegrationTest\Range(2, 2)), []);
+assert_throw(function () { foreach (new \IntegrationTest\Range(3, 1) as $value) {} }, "ErrorException", 0, "start is greater than end");
+assert_throw(function () use ($range) { foreach ($range as &$value) {} }, "Error", 0, "An iterator cannot be used with foreach by reference");
+
+// Test the cycles through state are collected.
+$holder = new \IntegrationTest\Holder();
+$obj = new stdClass();
+$obj->holder = $holder;
+$holder->hold($obj);
+$weak = WeakReference::create($obj);
+unset($obj, $holder);
+gc_collect_cycles();
+assert_eq($weak->get(), null);
+
+// Test userland subclasses.
+class ChildMoney extends \IntegrationTest\Money {
+    public $note = "child";
+
+    public function double() {
+        return $this->cents() * 2;
+    }
+}
+
+$child = new ChildMoney(25);
+assert_eq($child->cents(), 25);
+assert_eq($child->double(), 50);
+assert_eq($child->note, "child");
+assert_eq((string) $child, '$0.25');
+assert_eq(($child + $child)->cents(), 50);
+assert_true($child == new \IntegrationTest\Money(25));
+
+class ChildBag extends \IntegrationTest\Bag {}
+
+$child_bag = new ChildBag();
+$child_bag[] = "x";
+assert_eq(count($child_bag), 1);
+$copied = unserialize(serialize($child_bag));
+assert_true($copied instanceof ChildBag);
+assert_eq($copied[0], "x");
+
+class ChildRange extends \IntegrationTest\Range {}
+
+assert_eq(iterator_to_array(new ChildRange(0, 2)), ["k0" => 0, "k1" => 1]);
+
 // Test get or set static properties.
 assert_eq(IntegrationTest\PropsHolder::$foo, "bar");
 
@@ -68,3 +300,63 @@ assert_eq(IntegrationTest\PropsHolder::getFoo1(), 12345);
 $pre_foo1 = IntegrationTest\PropsHolder::setFoo1("baz");
 assert_eq($pre_foo1, 12345);
 assert_eq(IntegrationTest\PropsHolder::getFoo1(), "baz");
+
+// Test the constructor building state from arguments.
+$interval = new \IntegrationTest\Interval(3, 10);
+assert_eq($interval->length(), 7);
+assert_throw(function () { new \IntegrationTest\Interval(10, 3); }, "ErrorException", 0, "start is greater than end");
+assert_throw(function () { new \IntegrationTest\Interval("a", 3); }, "TypeError", 0, "type error: must be of type int, string given");
+$constructor = (new ReflectionClass(\IntegrationTest\Interval::class))->getConstructor();
+assert_eq($constructor->getNumberOfRequiredParameters(), 2);
+assert_eq((string) $constructor->getParameters()[1]->getType(), "int");
+
+$uninitialized = (new ReflectionClass(\IntegrationTest\Interval::class))->newInstanceWithoutConstructor();
+assert_throw(function () use ($uninitialized) { $uninitialized->length(); }, "Error", 0, "Object of class IntegrationTest\\Interval is not initialized");
+$uninitialized->__construct(1, 2);
+assert_eq($uninitialized->length(), 1);
+
+class ChildIntervalWithoutParent extends \IntegrationTest\Interval {
+    public function __construct() {}
+}
+
+class ChildInterval extends \IntegrationTest\Interval {
+    public function __construct() {
+        parent::__construct(0, 5);
+    }
+}
+
+assert_throw(function () { (new ChildIntervalWithoutParent())->length(); }, "Error", 0, "Object of class ChildIntervalWithoutParent is not initialized");
+assert_eq((new ChildInterval())->length(), 5);
+
+$unserialized = unserialize(serialize(new \IntegrationTest\Interval(2, 9)));
+assert_eq($unserialized->length(), 7);
+assert_eq(serialize($unserialized), serialize(new \IntegrationTest\Interval(2, 9)));
+assert_throw(function () { unserialize('O:24:"IntegrationTest\Interval":1:{s:5:"start";i:1;}'); }, "ErrorException", 0, "missing end");
+
+// Test the class defined by attributes.
+$counter = new \IntegrationTest\Counter(10);
+assert_eq($counter->increase(), 11);
+assert_eq($counter->increase(step: 5), 16);
+assert_eq($counter->getCount(), 16);
+assert_eq((new \IntegrationTest\Counter())->getCount(), 0);
+assert_eq($counter->label, "counter");
+assert_eq(\IntegrationTest\Counter::between(1, 3), 2);
+assert_throw(function () { \IntegrationTest\Counter::between(3, 1); }, "ErrorException", 0, "start is greater than end");
+
+$reflection_class = new ReflectionClass(\IntegrationTest\Counter::class);
+assert_true($reflection_class->getMethod("reset")->isPrivate());
+assert_true($reflection_class->getMethod("between")->isStatic());
+assert_true($reflection_class->getProperty("instances")->isProtected());
+assert_true($reflection_class->getProperty("instances")->isStatic());
+assert_eq($reflection_class->getConstructor()->getParameters()[0]->getName(), "start");
+assert_eq((string) $reflection_class->getMethod("getCount")->getReturnType(), "int");
+$uninitialized = $reflection_class->newInstanceWithoutConstructor();
+assert_throw(function () use ($uninitialized) { $uninitialized->getCount(); }, "Error", 0, "Object of class IntegrationTest\\Counter is not initialized");
+
+// Test the callable stored in state.
+$callback = new \IntegrationTest\Callback(function ($value) { return $value * 2; });
+gc_collect_cycles();
+assert_eq($callback->invoke(2), 4);
+assert_eq($callback->invoke(3), 6);
+$callback = new \IntegrationTest\Callback([new ArrayObject([1, 2]), "offsetGet"]);
+assert_eq($callback->invoke(1), 2);
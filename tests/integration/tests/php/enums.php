@@ -0,0 +1,56 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+use IntegrationTest\Level;
+use IntegrationTest\Status;
+use IntegrationTest\Suit;
+
+// Test pure enum.
+assert_true(enum_exists(Suit::class));
+assert_eq(Suit::cases(), [Suit::Hearts, Suit::Spades]);
+assert_eq(Suit::Hearts->name, "Hearts");
+assert_true(Suit::Hearts instanceof UnitEnum);
+assert_false(Suit::Hearts instanceof BackedEnum);
+assert_eq(Suit::Hearts->color(), "Red");
+assert_eq(Suit::Spades->color(), "Black");
+assert_eq(Suit::wild(), "Joker");
+assert_eq(Suit::DEFAULT_NAME, "Hearts");
+assert_throw(function () { new Suit(); }, "Error", 0, "Cannot instantiate enum IntegrationTest\\Suit");
+
+// Test int backed enum.
+assert_eq(Status::Active->value, 1);
+assert_true(Status::from(0) === Status::Inactive);
+assert_eq(Status::tryFrom(2), null);
+assert_true(Status::Active instanceof BackedEnum);
+assert_true(Status::Active instanceof \IntegrationTest\HasLabel);
+assert_eq(Status::Inactive->label(), "status 0");
+
+// Test string backed enum.
+assert_eq(Level::High->value, "high");
+assert_true(Level::from("low") === Level::Low);
+assert_eq(array_map(fn ($level) => $level->value, Level::cases()), ["low", "high"]);
+
+$reflection_enum = new ReflectionEnum(Level::class);
+assert_true($reflection_enum->isBacked());
+assert_eq((string) $reflection_enum->getBackingType(), "string");
+
+// Test the enum helpers.
+assert_true(integrate_enums_status_active() === Status::Active);
+assert_true(integrate_enums_status_case("Inactive") === Status::Inactive);
+assert_throw(function () { integrate_enums_status_case("Unknown"); }, "Error", 0, "Undefined enum case IntegrationTest\\Status::Unknown");
+assert_eq(integrate_enums_backing_value(Level::Low), "low");
+assert_eq(integrate_enums_backing_value(Suit::Hearts), null);
+assert_eq(integrate_enums_case_name(Suit::Spades), "Spades");
+assert_eq(integrate_enums_case_name(new stdClass()), null);
@@ -14,13 +14,110 @@
 require_once __DIR__ . '/_common.php';
 
 integrate_functions_call();
+integrate_functions_call_named();
+integrate_functions_call_by_ref();
 
 assert_eq(integrate_functions_call_callable(function () { return 1 + 1; }), 2);
 assert_eq(integrate_functions_call_callable(function ($a, $b) { return $a + $b; }, 1, 2), 3);
 assert_eq(integrate_functions_call_callable("addslashes", "Is your name O'Reilly?"), "Is your name O\'Reilly?");
+assert_eq(integrate_functions_call_callable(function (&$a) { $a = 2; return $a; }, 1), 2);
 assert_eq(integrate_functions_call_callable(array(new Exception("something wrong"), "getMessage")), "something wrong");
 assert_throw(function() {
     integrate_functions_call_callable(function () { throw new RuntimeException("oh no", 403); });
 }, "RuntimeException", 403, "oh no");
 
 assert_throw("integrate_functions_exception_guard", "ErrorException", 0, "other io error");
+
+assert_eq(integrate_functions_return_type_int(), 1);
+assert_eq(integrate_functions_return_type_void(), null);
+assert_eq(integrate_functions_return_type_nullable(), null);
+assert_eq((string) (new ReflectionFunction("integrate_functions_return_type_int"))->getReturnType(), "int");
+assert_eq((string) (new ReflectionFunction("integrate_functions_return_type_void"))->getReturnType(), "void");
+assert_eq((string) (new ReflectionFunction("integrate_functions_return_type_nullable"))->getReturnType(), "?stdClass");
+// The return value is checked in debug mode only.
+// The return value is only checked when php is built with debug mode.
+if (PHP_DEBUG) {
+    assert_throw("integrate_functions_return_type_mismatch", "TypeError", 0, "integrate_functions_return_type_mismatch(): Return value must be of type int, string returned");
+} else {
+    assert_eq(integrate_functions_return_type_mismatch(), "foo");
+}
+
+assert_eq(integrate_functions_typed_repeat("ab", 3), "ababab");
+assert_eq(integrate_functions_typed_repeat("ab", 3, ","), "ab,ab,ab");
+assert_eq(integrate_functions_typed_repeat("ab", times: 2, separator: null), "abab");
+assert_eq(integrate_functions_typed_count([1, 2, 3], 0), 3);
+assert_eq(integrate_functions_typed_count(null, 10), 10);
+assert_throw(function () { integrate_functions_typed_repeat("\xff", 1); }, "TypeError", 0, 'integrate_functions_typed_repeat(): Argument #1 ($s) must be of type string, string given');
+assert_throw(function () { integrate_functions_typed_repeat("ab", []); }, "TypeError", 0, 'integrate_functions_typed_repeat(): Argument #2 ($times) must be of type int, array given');
+assert_throw(function () { integrate_functions_typed_count(null); }, "ArgumentCountError", 0, "integrate_functions_typed_count(): expects at least 2 parameter(s), 1 given");
+
+$reflection = new ReflectionFunction("integrate_functions_typed_repeat");
+assert_eq(array_map(fn ($p) => $p->getName(), $reflection->getParameters()), ["s", "times", "separator"]);
+assert_eq(array_map(fn ($p) => (string) $p->getType(), $reflection->getParameters()), ["string", "int", "?string"]);
+assert_eq($reflection->getNumberOfRequiredParameters(), 2);
+assert_eq((string) $reflection->getReturnType(), "string");
+assert_eq((string) (new ReflectionFunction("integrate_functions_typed_count"))->getParameters()[0]->getType(), "?array");
+
+assert_eq(integrate_functions_attr_join(["a", "b"]), "a,b");
+assert_eq(integrate_functions_attr_join(["a", "b"], separator: "-"), "a-b");
+assert_eq(integrate_functions_attr_plus(1, 2), 3);
+assert_eq(integrate_functions_attr_noop(), null);
+assert_true(!function_exists("integrate_functions_attr_add"));
+
+$reflection = new ReflectionFunction("integrate_functions_attr_join");
+assert_eq(array_map(fn ($p) => $p->getName(), $reflection->getParameters()), ["pieces", "separator"]);
+assert_eq(array_map(fn ($p) => (string) $p->getType(), $reflection->getParameters()), ["array", "?string"]);
+assert_eq((string) $reflection->getReturnType(), "string");
+assert_eq((string) (new ReflectionFunction("integrate_functions_attr_plus"))->getReturnType(), "int");
+assert_eq((string) (new ReflectionFunction("integrate_functions_attr_noop"))->getReturnType(), "void");
+
+$closure = integrate_functions_closure_prefix("Hello, ");
+assert_true($closure instanceof Closure);
+assert_eq($closure("world"), "Hello, world");
+assert_eq(array_map($closure, ["foo", "bar"]), ["Hello, foo", "Hello, bar"]);
+assert_throw(function () use ($closure) { $closure(); }, "ArgumentCountError", 0, "{closure}(): expects at least 1 parameter(s), 0 given");
+assert_eq(array_map(fn ($p) => $p->getName(), (new ReflectionFunction($closure))->getParameters()), ["name"]);
+unset($closure);
+
+$closure = integrate_functions_closure_typed();
+assert_eq($closure(3), 6);
+assert_eq($closure(3, 4), 12);
+$reflection = new ReflectionFunction($closure);
+assert_eq(array_map(fn ($p) => (string) $p->getType(), $reflection->getParameters()), ["int", "?int"]);
+assert_eq($reflection->getNumberOfRequiredParameters(), 1);
+assert_eq((string) $reflection->getReturnType(), "int");
+
+integrate_functions_closure_drop();
+
+// The copied closures share the data with the origin one, which is freed first.
+$closure = integrate_functions_closure_prefix("Hi, ");
+$bound = $closure->bindTo(null);
+$static_bound = Closure::bind($closure, null);
+$from_reflection = (new ReflectionFunction($closure))->getClosure();
+$cloned = clone $closure;
+unset($closure);
+assert_eq($bound("a"), "Hi, a");
+assert_eq($static_bound("b"), "Hi, b");
+assert_eq($from_reflection("c"), "Hi, c");
+assert_eq($cloned("d"), "Hi, d");
+assert_eq((new ReflectionFunction($bound))->getParameters()[0]->getName(), "name");
+unset($bound, $static_bound, $from_reflection, $cloned);
+
+// The copy of copy outlives both the origin closure and the first copy.
+$closure = integrate_functions_closure_prefix("Hey, ");
+$bound = Closure::bind($closure, null);
+unset($closure);
+$rebound = clone $bound->bindTo(null);
+unset($bound);
+assert_eq($rebound("e"), "Hey, e");
+unset($rebound);
+
+integrate_functions_closure_bound_drop();
+
+assert_eq(integrate_functions_callable_sum(fn ($n) => $n * 2, [1, 2, 3]), 12);
+assert_eq(integrate_functions_callable_sum("abs", [-1, -2]), 3);
+assert_eq(integrate_functions_callable_sum([new ArrayObject([10, 20]), "offsetGet"], [0, 1]), 30);
+assert_eq((string) (new ReflectionFunction("integrate_functions_callable_sum"))->getParameters()[0]->getType(), "callable");
+assert_throw(function () { integrate_functions_callable_sum(fn ($n) => throw new RuntimeException("oh no", 1), [1]); }, "RuntimeException", 1, "oh no");
+assert_throw(function () { integrate_functions_callable_new("integrate_functions_not_exists"); }, "TypeError", 0, 'Expected a valid callback, function "integrate_functions_not_exists" not found or invalid function name');
+assert_throw(function () { integrate_functions_callable_new(1); }, "TypeError", 0, "Expected a valid callback, no array or string given");